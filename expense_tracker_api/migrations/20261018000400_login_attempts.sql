@@ -0,0 +1,9 @@
+-- Failed login tracking per username, client IP and second factor
+CREATE TABLE IF NOT EXISTS login_attempts (
+    scope TEXT NOT NULL,
+    key TEXT NOT NULL,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failure_at TEXT NOT NULL DEFAULT(datetime('now')),
+    locked_until TEXT,
+    PRIMARY KEY (scope, key)
+);
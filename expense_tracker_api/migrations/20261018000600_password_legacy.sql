@@ -0,0 +1,5 @@
+-- Hashes made before passwords stopped being trimmed and lowercased.
+-- Login still accepts the old form for these rows and rehashes on success.
+ALTER TABLE users ADD COLUMN password_legacy INTEGER NOT NULL DEFAULT 0;
+
+UPDATE users SET password_legacy = 1;
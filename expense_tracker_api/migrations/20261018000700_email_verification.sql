@@ -0,0 +1,7 @@
+-- Email verification. Accounts created before this existed keep working as
+-- verified, but their address was never confirmed, so it is flagged and not
+-- trusted as proof of ownership (OIDC will not link to it by email).
+ALTER TABLE users ADD COLUMN email_verified_at TEXT;
+ALTER TABLE users ADD COLUMN email_verified_legacy INTEGER NOT NULL DEFAULT 0;
+
+UPDATE users SET email_verified_at = created_at, email_verified_legacy = 1;
@@ -0,0 +1,16 @@
+-- Per-user roles, account disabling and an audit trail of admin actions
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'user'
+    CHECK (role IN ('user', 'admin', 'auditor'));
+ALTER TABLE users ADD COLUMN disabled_at TEXT;
+
+-- No foreign keys: entries must outlive the accounts they mention.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    target_user_id INTEGER,
+    details TEXT,
+    created_at TEXT NOT NULL DEFAULT(datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_target_user_id ON audit_log(target_user_id);
@@ -0,0 +1,2 @@
+-- Address a user asked to switch to, kept until they confirm it
+ALTER TABLE users ADD COLUMN pending_email TEXT;
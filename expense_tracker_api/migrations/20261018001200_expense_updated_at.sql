@@ -0,0 +1,4 @@
+-- Track when an expense last changed, for Last-Modified and conditional requests
+ALTER TABLE expenses ADD COLUMN updated_at TEXT;
+
+UPDATE expenses SET updated_at = created_at;
@@ -0,0 +1,29 @@
+-- Full-text index over expenses, kept in sync by triggers
+CREATE VIRTUAL TABLE IF NOT EXISTS expenses_fts USING fts5(
+    expense_desc,
+    category,
+    amount,
+    content = 'expenses',
+    content_rowid = 'id',
+    tokenize = 'unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS expenses_fts_insert AFTER INSERT ON expenses BEGIN
+    INSERT INTO expenses_fts (rowid, expense_desc, category, amount)
+    VALUES (new.id, new.expense_desc, new.category, new.amount);
+END;
+
+CREATE TRIGGER IF NOT EXISTS expenses_fts_delete AFTER DELETE ON expenses BEGIN
+    INSERT INTO expenses_fts (expenses_fts, rowid, expense_desc, category, amount)
+    VALUES ('delete', old.id, old.expense_desc, old.category, old.amount);
+END;
+
+CREATE TRIGGER IF NOT EXISTS expenses_fts_update
+AFTER UPDATE OF expense_desc, category, amount ON expenses BEGIN
+    INSERT INTO expenses_fts (expenses_fts, rowid, expense_desc, category, amount)
+    VALUES ('delete', old.id, old.expense_desc, old.category, old.amount);
+    INSERT INTO expenses_fts (rowid, expense_desc, category, amount)
+    VALUES (new.id, new.expense_desc, new.category, new.amount);
+END;
+
+INSERT INTO expenses_fts (expenses_fts) VALUES ('rebuild');
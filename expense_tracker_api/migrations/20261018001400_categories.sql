@@ -0,0 +1,37 @@
+-- Per-user categories, seeded with the eight that used to be built in
+CREATE TABLE IF NOT EXISTS categories (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL COLLATE NOCASE,
+    color TEXT,
+    icon TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS expenses_user_category ON expenses (user_id, category);
+
+-- Every way an account gets created starts it off with the defaults.
+CREATE TRIGGER IF NOT EXISTS users_seed_categories AFTER INSERT ON users BEGIN
+    INSERT INTO categories (user_id, name)
+    VALUES
+        (new.id, 'Food'),
+        (new.id, 'Fare'),
+        (new.id, 'Groceries'),
+        (new.id, 'Leisure'),
+        (new.id, 'Electronics'),
+        (new.id, 'Utilities'),
+        (new.id, 'Clothing'),
+        (new.id, 'Health');
+END;
+
+INSERT OR IGNORE INTO categories (user_id, name)
+SELECT users.id, defaults.column1
+FROM users
+CROSS JOIN (
+    VALUES ('Food'), ('Fare'), ('Groceries'), ('Leisure'),
+        ('Electronics'), ('Utilities'), ('Clothing'), ('Health')
+) AS defaults;
+
+INSERT OR IGNORE INTO categories (user_id, name)
+SELECT DISTINCT user_id, category FROM expenses;
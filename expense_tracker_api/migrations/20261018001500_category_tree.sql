@@ -0,0 +1,4 @@
+-- Categories nest under a parent, e.g. Food > Coffee
+ALTER TABLE categories ADD COLUMN parent_id INTEGER REFERENCES categories(id);
+
+CREATE INDEX IF NOT EXISTS categories_parent ON categories (parent_id);
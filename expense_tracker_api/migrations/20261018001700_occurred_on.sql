@@ -0,0 +1,12 @@
+-- When an expense happened, kept apart from when it was recorded
+ALTER TABLE expenses ADD COLUMN occurred_on TEXT;
+ALTER TABLE expenses ADD COLUMN occurred_time TEXT;
+ALTER TABLE expenses ADD COLUMN timezone TEXT;
+
+UPDATE expenses
+SET occurred_on = substr(created_at, 1, 10),
+    timezone = 'UTC';
+
+ALTER TABLE users ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
+
+CREATE INDEX IF NOT EXISTS expenses_user_occurred_on ON expenses (user_id, occurred_on);
@@ -0,0 +1,13 @@
+-- Amounts are kept in the currency's minor unit.
+--
+-- Amounts so far were whole units with no currency recorded. They are assumed
+-- to be in a currency with 2 decimal places and tagged USD, so each one is
+-- multiplied by 100. A deployment that recorded another currency should retag
+-- its data once all migrations have run, e.g. for EUR:
+--   UPDATE expenses SET currency = 'EUR', base_currency = 'EUR';
+--   UPDATE users SET base_currency = 'EUR';
+-- A currency without 2 decimal places also needs `amount` and `base_amount`
+-- rescaled, e.g. divided by 100 for JPY.
+ALTER TABLE expenses ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD';
+
+UPDATE expenses SET amount = amount * 100;
@@ -0,0 +1,19 @@
+-- Exchange rates, a base currency per user and the conversion frozen on each expense
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    base TEXT NOT NULL,
+    quote TEXT NOT NULL,
+    rate_on TEXT NOT NULL,
+    rate REAL NOT NULL CHECK (rate > 0),
+    PRIMARY KEY (base, quote, rate_on)
+);
+
+ALTER TABLE users ADD COLUMN base_currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE expenses ADD COLUMN base_amount INTEGER;
+ALTER TABLE expenses ADD COLUMN base_currency TEXT;
+ALTER TABLE expenses ADD COLUMN exchange_rate REAL;
+
+UPDATE expenses
+SET base_amount = amount,
+    base_currency = currency,
+    exchange_rate = 1.0;
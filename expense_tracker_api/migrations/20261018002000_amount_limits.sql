@@ -0,0 +1,13 @@
+-- Caps on a single expense in whole units of the user's base currency:
+-- defaults set by an admin, overridable per user
+CREATE TABLE IF NOT EXISTS app_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    max_expense_amount INTEGER,
+    confirm_above INTEGER
+);
+
+INSERT OR IGNORE INTO app_settings (id, max_expense_amount, confirm_above)
+VALUES (1, 300000, NULL);
+
+ALTER TABLE users ADD COLUMN max_expense_amount INTEGER;
+ALTER TABLE users ADD COLUMN confirm_above INTEGER;
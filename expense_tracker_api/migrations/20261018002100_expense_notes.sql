@@ -0,0 +1,39 @@
+-- Optional notes and merchant on expenses, both searchable
+ALTER TABLE expenses ADD COLUMN notes TEXT;
+ALTER TABLE expenses ADD COLUMN merchant TEXT;
+
+DROP TRIGGER IF EXISTS expenses_fts_insert;
+DROP TRIGGER IF EXISTS expenses_fts_delete;
+DROP TRIGGER IF EXISTS expenses_fts_update;
+DROP TABLE IF EXISTS expenses_fts;
+
+CREATE VIRTUAL TABLE IF NOT EXISTS expenses_fts USING fts5(
+    expense_desc,
+    category,
+    amount,
+    merchant,
+    notes,
+    content = 'expenses',
+    content_rowid = 'id',
+    tokenize = 'unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS expenses_fts_insert AFTER INSERT ON expenses BEGIN
+    INSERT INTO expenses_fts (rowid, expense_desc, category, amount, merchant, notes)
+    VALUES (new.id, new.expense_desc, new.category, new.amount, new.merchant, new.notes);
+END;
+
+CREATE TRIGGER IF NOT EXISTS expenses_fts_delete AFTER DELETE ON expenses BEGIN
+    INSERT INTO expenses_fts (expenses_fts, rowid, expense_desc, category, amount, merchant, notes)
+    VALUES ('delete', old.id, old.expense_desc, old.category, old.amount, old.merchant, old.notes);
+END;
+
+CREATE TRIGGER IF NOT EXISTS expenses_fts_update
+AFTER UPDATE OF expense_desc, category, amount, merchant, notes ON expenses BEGIN
+    INSERT INTO expenses_fts (expenses_fts, rowid, expense_desc, category, amount, merchant, notes)
+    VALUES ('delete', old.id, old.expense_desc, old.category, old.amount, old.merchant, old.notes);
+    INSERT INTO expenses_fts (rowid, expense_desc, category, amount, merchant, notes)
+    VALUES (new.id, new.expense_desc, new.category, new.amount, new.merchant, new.notes);
+END;
+
+INSERT INTO expenses_fts (expenses_fts) VALUES ('rebuild');
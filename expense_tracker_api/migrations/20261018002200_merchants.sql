@@ -0,0 +1,23 @@
+-- Per-user merchants with a default category and alternative spellings
+CREATE TABLE IF NOT EXISTS merchants (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL COLLATE NOCASE,
+    category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (user_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS merchant_aliases (
+    merchant_id INTEGER NOT NULL REFERENCES merchants(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    alias TEXT NOT NULL COLLATE NOCASE,
+    UNIQUE (user_id, alias)
+);
+
+CREATE INDEX IF NOT EXISTS merchant_aliases_merchant ON merchant_aliases (merchant_id);
+CREATE INDEX IF NOT EXISTS expenses_user_merchant ON expenses (user_id, merchant);
+
+-- Every merchant already typed on an expense becomes one
+INSERT OR IGNORE INTO merchants (user_id, name)
+SELECT DISTINCT user_id, merchant FROM expenses WHERE merchant IS NOT NULL;
@@ -0,0 +1,62 @@
+use crate::api::dto::ApiError;
+use serde_json::Value;
+
+/// Admin API actions recorded in `audit_log`.
+#[derive(Clone, Copy)]
+pub enum AuditAction {
+    ListUsers,
+    ViewExpenses,
+    DisableUser,
+    EnableUser,
+    ForceLogout,
+    ChangeRole,
+    ImportExchangeRates,
+    UpdateSettings,
+    ViewSettings,
+    ViewAuditLog,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::ListUsers => "list_users",
+            AuditAction::ViewExpenses => "view_expenses",
+            AuditAction::DisableUser => "disable_user",
+            AuditAction::EnableUser => "enable_user",
+            AuditAction::ForceLogout => "force_logout",
+            AuditAction::ChangeRole => "change_role",
+            AuditAction::ImportExchangeRates => "import_exchange_rates",
+            AuditAction::UpdateSettings => "update_settings",
+            AuditAction::ViewSettings => "view_settings",
+            AuditAction::ViewAuditLog => "view_audit_log",
+        }
+    }
+}
+
+/// Appends an entry; pass the transaction of the action itself so the two
+/// are committed together.
+pub async fn record<'e, E>(
+    executor: E,
+    actor_id: i64,
+    action: AuditAction,
+    target_user_id: Option<i64>,
+    details: Option<Value>,
+) -> Result<(), ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO audit_log (actor_id, action, target_user_id, details)
+        VALUES (?1, ?2, ?3, ?4)
+    "#,
+    )
+    .bind(actor_id)
+    .bind(action.as_str())
+    .bind(target_user_id)
+    .bind(details.map(|details| details.to_string()))
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
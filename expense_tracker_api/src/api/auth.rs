@@ -1,13 +1,48 @@
-use crate::api::dto::{ApiError, Claims};
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims},
+    tokens::{API_TOKEN_PREFIX, hash_token},
+};
+use crate::domain::{Scope, user_types::Role};
 use axum::{
-    extract::Request,
-    http::{HeaderMap, header::AUTHORIZATION},
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{
+        HeaderMap, Method,
+        header::{AUTHORIZATION, USER_AGENT},
+        request::Parts,
+    },
     middleware::Next,
     response::IntoResponse,
 };
-use jsonwebtoken::{DecodingKey, Validation, decode};
+use sqlx::prelude::FromRow;
+use std::{convert::Infallible, marker::PhantomData, net::SocketAddr};
+
+const USER_AGENT_MAX_CHARS: usize = 256;
+/// `sessions.last_seen_at` is only rewritten once it is older than this.
+const LAST_SEEN_RESOLUTION_SECS: i64 = 5 * 60;
+
+/// Scopes the current request was authenticated with, inserted next to `Claims`.
+#[derive(Clone)]
+pub struct GrantedScopes(pub Vec<Scope>);
+
+/// What accounts with an unverified email address may do.
+#[derive(Clone, Copy)]
+pub enum UnverifiedPolicy {
+    Allow,
+    ReadOnly,
+    Block,
+}
+
+#[derive(FromRow)]
+struct ApiTokenAuth {
+    user_id: i64,
+    scopes: String,
+    iat: i64,
+    exp: Option<i64>,
+}
 
 pub async fn auth(
+    State(state): State<AppState>,
     headers: HeaderMap,
     mut request: Request,
     next: Next,
@@ -21,15 +56,222 @@ pub async fn auth(
         .strip_prefix("Bearer ")
         .ok_or(ApiError::Unauthorized)?;
 
-    let key = std::env::var("SECRET_KEY")?;
+    let (claims, scopes) = if token.starts_with(API_TOKEN_PREFIX) {
+        api_token_claims(&state, token).await?
+    } else {
+        (session_claims(&state, token).await?, Scope::ALL.to_vec())
+    };
+
+    request.extensions_mut().insert(claims);
+    request.extensions_mut().insert(GrantedScopes(scopes));
+
+    Ok(next.run(request).await)
+}
+
+async fn session_claims(state: &AppState, token: &str) -> Result<Claims, ApiError> {
+    let mut claims: Claims = state
+        .keyring
+        .decode(token, None)
+        .map_err(|_| ApiError::Unauthorized)?;
+
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+    let sid = claims.sid.as_deref().ok_or(ApiError::Unauthorized)?;
+
+    // Access tokens stay valid only as long as their session has not been revoked.
+    let (role, disabled, stale): (String, bool, bool) = sqlx::query_as(
+        r#"
+        SELECT
+            u.role,
+            u.disabled_at IS NOT NULL,
+            s.last_seen_at IS NULL OR s.last_seen_at <= datetime('now', ?3)
+        FROM sessions s
+        JOIN users u ON u.id = s.user_id
+        WHERE s.id = ?1 AND s.user_id = ?2 AND s.revoked_at IS NULL
+    "#,
+    )
+    .bind(sid)
+    .bind(user_id)
+    .bind(format!("-{LAST_SEEN_RESOLUTION_SECS} seconds"))
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::Unauthorized)?;
+
+    if disabled {
+        return Err(ApiError::Forbidden("This account is disabled"));
+    }
+    // Reads are free; the write only happens every few minutes per session.
+    if stale {
+        sqlx::query(
+            r#"
+            UPDATE sessions SET last_seen_at = datetime('now') WHERE id = ?1
+        "#,
+        )
+        .bind(sid)
+        .execute(&state.pool)
+        .await?;
+    }
+    // The stored role wins over the one in the token, so demotions apply at once.
+    claims.role = Role::try_from(role.as_str()).map_err(|_| ApiError::Internal)?;
+
+    Ok(claims)
+}
+
+async fn api_token_claims(state: &AppState, token: &str) -> Result<(Claims, Vec<Scope>), ApiError> {
+    let row = sqlx::query_as::<_, ApiTokenAuth>(
+        r#"
+        UPDATE api_tokens
+        SET last_used_at = datetime('now')
+        WHERE token_hash = ?1
+            AND revoked_at IS NULL
+            AND (expires_at IS NULL OR expires_at > datetime('now'))
+            AND user_id IN (SELECT id FROM users WHERE disabled_at IS NULL)
+        RETURNING
+            user_id,
+            scopes,
+            CAST(strftime('%s', created_at) AS INTEGER) AS iat,
+            CAST(strftime('%s', expires_at) AS INTEGER) AS exp
+    "#,
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::Unauthorized)?;
+
+    let scopes = Scope::parse_list(&row.scopes).map_err(|_| ApiError::Internal)?;
+
+    let claims = Claims {
+        sub: row.user_id.to_string(),
+        sid: None,
+        // Personal access tokens never carry elevated roles.
+        role: Role::User,
+        exp: row.exp.unwrap_or(i64::MAX),
+        iat: row.iat,
+    };
+
+    Ok((claims, scopes))
+}
+
+/// Route layer rejecting requests whose credentials lack `required`.
+/// Mount with `middleware::from_fn_with_state(Scope::..., require_scope)`.
+pub async fn require_scope(
+    State(required): State<Scope>,
+    request: Request,
+    next: Next,
+) -> Result<impl IntoResponse, ApiError> {
+    let granted = request
+        .extensions()
+        .get::<GrantedScopes>()
+        .ok_or(ApiError::Unauthorized)?;
+
+    if !granted.0.contains(&required) {
+        return Err(ApiError::Forbidden("token is missing the required scope"));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Where a login comes from, recorded on the session it opens.
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(USER_AGENT_MAX_CHARS).collect());
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+        Ok(Self { user_agent, ip })
+    }
+}
+
+/// Role sets usable with `RequireRole`.
+pub trait AllowedRoles {
+    const ROLES: &'static [Role];
+}
+
+pub struct AdminOnly;
+
+impl AllowedRoles for AdminOnly {
+    const ROLES: &'static [Role] = &[Role::Admin];
+}
+
+/// Admins and auditors, for the read-only parts of the admin API.
+pub struct AdminOrAuditor;
+
+impl AllowedRoles for AdminOrAuditor {
+    const ROLES: &'static [Role] = &[Role::Admin, Role::Auditor];
+}
+
+/// Extracts the caller's `Claims`, rejecting roles outside `R::ROLES`.
+/// Only works on routes behind `auth`.
+pub struct RequireRole<R: AllowedRoles>(pub Claims, pub PhantomData<R>);
+
+impl<S, R> FromRequestParts<S> for RequireRole<R>
+where
+    S: Send + Sync,
+    R: AllowedRoles,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let claims = parts
+            .extensions
+            .get::<Claims>()
+            .cloned()
+            .ok_or(ApiError::Unauthorized)?;
+
+        if !R::ROLES.contains(&claims.role) {
+            return Err(ApiError::Forbidden("Your role does not allow this action"));
+        }
+
+        Ok(Self(claims, PhantomData))
+    }
+}
+
+/// Route layer applying `AppState.unverified_policy`; must run after `auth`.
+pub async fn require_verified_email(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<impl IntoResponse, ApiError> {
+    let read_only = matches!(request.method(), &Method::GET | &Method::HEAD);
+    let restricted = match state.unverified_policy {
+        UnverifiedPolicy::Allow => false,
+        UnverifiedPolicy::ReadOnly => !read_only,
+        UnverifiedPolicy::Block => true,
+    };
+
+    if restricted {
+        let claims = request
+            .extensions()
+            .get::<Claims>()
+            .ok_or(ApiError::Unauthorized)?;
+        let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
 
-    let token_msg = decode::<Claims>(
-        &token,
-        &DecodingKey::from_secret(key.as_bytes()),
-        &Validation::default(),
-    )?;
+        let verified: bool = sqlx::query_scalar(
+            r#"
+            SELECT email_verified_at IS NOT NULL FROM users WHERE id = ?1
+        "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .unwrap_or(false);
 
-    request.extensions_mut().insert(token_msg.claims);
+        if !verified {
+            return Err(ApiError::Forbidden("verify your email address first"));
+        }
+    }
 
     Ok(next.run(request).await)
 }
@@ -0,0 +1,266 @@
+use crate::api::dto::{ApiError, CategoryResponseDTO};
+use crate::domain::Category;
+use chrono::Utc;
+use sqlx::SqliteConnection;
+
+/// Levels in a category tree, counting the top-level category.
+pub const MAX_CATEGORY_DEPTH: i64 = 3;
+
+/// Looks `name` up among the user's categories and returns it as stored, so
+/// expenses always carry the category's own spelling. Names are unique per
+/// user across the whole tree, so any node can be named on its own.
+pub async fn resolve<'e, E>(
+    executor: E,
+    user_id: i64,
+    name: &Category,
+) -> Result<Category, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let stored: String = sqlx::query_scalar(
+        r#"
+        SELECT name FROM categories WHERE user_id = ?1 AND name = ?2
+    "#,
+    )
+    .bind(user_id)
+    .bind(name.as_str())
+    .fetch_optional(executor)
+    .await?
+    .ok_or(ApiError::BadRequest("Unknown category, create it first"))?;
+
+    Ok(Category::try_from(stored)?)
+}
+
+/// The user's categories in tree order, each with its path from the top and
+/// the count of expenses filed directly under it; only `id` when given.
+pub async fn list<'e, E>(
+    executor: E,
+    user_id: i64,
+    id: Option<i64>,
+) -> Result<Vec<CategoryResponseDTO>, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let categories = sqlx::query_as(
+        r#"
+        WITH RECURSIVE paths(id, path, depth) AS (
+            SELECT id, name, 1 FROM categories WHERE user_id = ?1 AND parent_id IS NULL
+            UNION ALL
+            SELECT c.id, paths.path || ' > ' || c.name, paths.depth + 1
+            FROM categories c
+            JOIN paths ON c.parent_id = paths.id
+        )
+        SELECT c.id, c.name, c.parent_id, paths.path, paths.depth, c.color, c.icon,
+            c.created_at, COUNT(e.id) AS expense_count
+        FROM categories c
+        JOIN paths ON paths.id = c.id
+        LEFT JOIN expenses e ON e.user_id = c.user_id AND e.category = c.name
+        WHERE ?2 IS NULL OR c.id = ?2
+        GROUP BY c.id
+        ORDER BY paths.path
+    "#,
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(categories)
+}
+
+/// One of the user's categories.
+pub async fn fetch<'e, E>(
+    executor: E,
+    user_id: i64,
+    id: i64,
+) -> Result<CategoryResponseDTO, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    list(executor, user_id, Some(id))
+        .await?
+        .pop()
+        .ok_or(ApiError::NotFound("category not found"))
+}
+
+/// Checks that category `id`, or a new one when `None`, can go under
+/// `parent_id`: the parent must be the user's, must not be `id` or below it,
+/// and the tree must stay within `MAX_CATEGORY_DEPTH`.
+pub async fn check_parent(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    id: Option<i64>,
+    parent_id: i64,
+) -> Result<(), ApiError> {
+    // The depth guard keeps the walk finite even if a cycle slipped in.
+    let (parent_depth, is_own_descendant): (Option<i64>, bool) = sqlx::query_as(
+        r#"
+        WITH RECURSIVE up(id, parent_id, depth) AS (
+            SELECT id, parent_id, 1 FROM categories WHERE id = ?1 AND user_id = ?2
+            UNION ALL
+            SELECT c.id, c.parent_id, up.depth + 1
+            FROM categories c
+            JOIN up ON c.id = up.parent_id
+            WHERE up.depth <= ?4
+        )
+        SELECT MAX(depth), COALESCE(MAX(id = ?3), 0) FROM up
+    "#,
+    )
+    .bind(parent_id)
+    .bind(user_id)
+    .bind(id)
+    .bind(MAX_CATEGORY_DEPTH)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let parent_depth = parent_depth.ok_or(ApiError::NotFound("parent category not found"))?;
+    if is_own_descendant {
+        return Err(ApiError::BadRequest(
+            "A category cannot be placed under itself or its subcategories",
+        ));
+    }
+
+    let height: i64 = match id {
+        Some(id) => {
+            sqlx::query_scalar(
+                r#"
+                WITH RECURSIVE down(id, depth) AS (
+                    SELECT id, 1 FROM categories WHERE id = ?1
+                    UNION
+                    SELECT c.id, down.depth + 1
+                    FROM categories c
+                    JOIN down ON c.parent_id = down.id
+                    WHERE down.depth <= ?2
+                )
+                SELECT MAX(depth) FROM down
+            "#,
+            )
+            .bind(id)
+            .bind(MAX_CATEGORY_DEPTH)
+            .fetch_one(&mut *conn)
+            .await?
+        }
+        None => 1,
+    };
+
+    if parent_depth + height > MAX_CATEGORY_DEPTH {
+        return Err(ApiError::BadRequest(
+            "Categories can nest at most 3 levels deep",
+        ));
+    }
+    Ok(())
+}
+
+/// Moves every expense filed under `from` to `to`; returns how many moved.
+pub async fn reassign_expenses<'e, E>(
+    executor: E,
+    user_id: i64,
+    from: &str,
+    to: &str,
+) -> Result<u64, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE expenses
+        SET category = ?3, updated_at = ?4
+        WHERE user_id = ?1 AND category = ?2
+    "#,
+    )
+    .bind(user_id)
+    .bind(from)
+    .bind(to)
+    .bind(Utc::now().to_rfc3339())
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Moves the expenses and merchant defaults of category `from_id` into
+/// `into_id` and deletes `from_id`, which must have no subcategories.
+/// Returns the surviving category and how many expenses moved.
+pub async fn merge(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    from_id: i64,
+    into_id: i64,
+) -> Result<(CategoryResponseDTO, u64), ApiError> {
+    if from_id == into_id {
+        return Err(ApiError::BadRequest("Cannot merge a category into itself"));
+    }
+
+    let from = fetch(&mut *conn, user_id, from_id).await?;
+    let into = fetch(&mut *conn, user_id, into_id).await?;
+    ensure_leaf(conn, from_id).await?;
+    let moved = reassign_expenses(&mut *conn, user_id, &from.name, &into.name).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE merchants SET category_id = ?2 WHERE category_id = ?1
+    "#,
+    )
+    .bind(from_id)
+    .bind(into_id)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM categories WHERE id = ?1 AND user_id = ?2
+    "#,
+    )
+    .bind(from_id)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok((fetch(&mut *conn, user_id, into_id).await?, moved))
+}
+
+/// Deletes a category that has neither subcategories nor expenses.
+pub async fn delete_empty(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    id: i64,
+) -> Result<(), ApiError> {
+    let category = fetch(&mut *conn, user_id, id).await?;
+    ensure_leaf(conn, id).await?;
+    if category.expense_count > 0 {
+        return Err(ApiError::Conflict(
+            "Category still has expenses, pass reassign_to to move them",
+        ));
+    }
+
+    sqlx::query(
+        r#"
+        DELETE FROM categories WHERE id = ?1 AND user_id = ?2
+    "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Subcategories have to be moved or removed before their parent can go.
+async fn ensure_leaf(conn: &mut SqliteConnection, id: i64) -> Result<(), ApiError> {
+    let children: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM categories WHERE parent_id = ?1
+    "#,
+    )
+    .bind(id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if children > 0 {
+        return Err(ApiError::Conflict(
+            "Category has subcategories, move or delete them first",
+        ));
+    }
+    Ok(())
+}
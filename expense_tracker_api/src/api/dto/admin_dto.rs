@@ -0,0 +1,106 @@
+use crate::api::dto::{ApiError, category_dto::present};
+use crate::domain::{Currency, ExchangeRate, expense_types::parse_date};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::prelude::FromRow;
+
+/// An account as seen through the admin API.
+#[derive(Serialize, FromRow)]
+pub struct AdminUserResponseDTO {
+    pub id: i64,
+    pub username: String,
+    pub email: String,
+    pub role: String,
+    pub email_verified_at: Option<String>,
+    pub disabled_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Deserialize)]
+pub struct SetRoleRequestDTO {
+    pub role: String,
+}
+
+#[derive(Deserialize)]
+pub struct ExchangeRatesRequestDTO {
+    pub rates: Vec<ExchangeRateDTO>,
+}
+
+/// One unit of `base` buys `rate` units of `quote` on `date`.
+#[derive(Deserialize)]
+pub struct ExchangeRateDTO {
+    pub date: String,
+    pub base: String,
+    pub quote: String,
+    pub rate: f64,
+}
+
+impl TryFrom<ExchangeRatesRequestDTO> for Vec<ExchangeRate> {
+    type Error = ApiError;
+    fn try_from(input: ExchangeRatesRequestDTO) -> Result<Self, Self::Error> {
+        input
+            .rates
+            .into_iter()
+            .map(|rate| {
+                Ok(ExchangeRate::new(
+                    Currency::try_from(rate.base.as_str())?,
+                    Currency::try_from(rate.quote.as_str())?,
+                    parse_date(&rate.date)?,
+                    rate.rate,
+                )?)
+            })
+            .collect()
+    }
+}
+
+#[derive(FromRow)]
+pub struct AuditLogDbRow {
+    id: i64,
+    actor_id: i64,
+    action: String,
+    target_user_id: Option<i64>,
+    details: Option<String>,
+    created_at: String,
+}
+
+#[derive(Serialize)]
+pub struct AuditLogEntryDTO {
+    id: i64,
+    actor_id: i64,
+    action: String,
+    target_user_id: Option<i64>,
+    details: Option<Value>,
+    created_at: String,
+}
+
+impl From<AuditLogDbRow> for AuditLogEntryDTO {
+    fn from(row: AuditLogDbRow) -> Self {
+        Self {
+            id: row.id,
+            actor_id: row.actor_id,
+            action: row.action,
+            target_user_id: row.target_user_id,
+            details: row
+                .details
+                .and_then(|details| serde_json::from_str(&details).ok()),
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Amount limits every user gets unless they set their own, in whole units
+/// of each user's base currency; `null` leaves that limit off.
+#[derive(Serialize, FromRow)]
+pub struct AppSettingsDTO {
+    pub max_expense_amount: Option<i64>,
+    pub confirm_above: Option<i64>,
+}
+
+/// Omitted fields are left alone; `null` turns the limit off.
+#[derive(Deserialize)]
+pub struct UpdateAppSettingsRequestDTO {
+    #[serde(default, deserialize_with = "present")]
+    pub max_expense_amount: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present")]
+    pub confirm_above: Option<Option<i64>>,
+}
@@ -1,11 +1,16 @@
-use crate::domain::errors::{DomainError, ValidationError};
+use crate::api::dto::MoneyDTO;
+use crate::domain::{
+    LimitBreach,
+    errors::{DomainError, ValidationError},
+};
 use argon2::password_hash;
 use axum::{
     Json,
-    http::{self, StatusCode},
+    http::{self, StatusCode, header::RETRY_AFTER},
     response::{IntoResponse, Response},
 };
 use serde::Serialize;
+use serde_json::{Value, json};
 use thiserror::{self, Error};
 #[derive(Debug, Error)]
 pub enum ApiError {
@@ -19,29 +24,69 @@ pub enum ApiError {
     Internal,
     #[error("Invalid credentials")]
     Unauthorized,
+    #[error("{0}")]
+    Forbidden(&'static str),
+    #[error("Too many requests, retry later")]
+    TooManyRequests { retry_after: u64 },
+    #[error("Account temporarily locked, retry later")]
+    Locked { retry_after: u64 },
+    #[error("{0}")]
+    BadGateway(&'static str),
+    #[error("{0}")]
+    AmountLimit(LimitBreach),
 }
 
 #[derive(Debug, Serialize)]
 struct ErrorBody {
     error: String,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    details: Option<Value>,
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        let retry_after = match self {
+            ApiError::TooManyRequests { retry_after } | ApiError::Locked { retry_after } => {
+                Some(retry_after)
+            }
+            _ => None,
+        };
         let status = match self {
             ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
             ApiError::Conflict(_) => StatusCode::CONFLICT,
             ApiError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Locked { .. } => StatusCode::LOCKED,
+            ApiError::BadGateway(_) => StatusCode::BAD_GATEWAY,
+            ApiError::AmountLimit(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        // Enough for a client to tell the user what to change.
+        let details = match &self {
+            ApiError::AmountLimit(breach) => Some(json!({
+                "code": breach.kind.as_str(),
+                "amount": MoneyDTO::from(breach.amount),
+                "limit": MoneyDTO::from(breach.limit),
+            })),
+            _ => None,
         };
-        (
+        let mut response = (
             status,
             Json(ErrorBody {
                 error: self.to_string(),
+                details,
             }),
         )
-            .into_response()
+            .into_response();
+
+        if let Some(secs) = retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, http::HeaderValue::from(secs));
+        }
+        response
     }
 }
 
@@ -63,8 +108,47 @@ impl From<ValidationError> for ApiError {
                 ApiError::BadRequest("Field must start with alphanumeric character")
             }
             ValidationError::InvalidFormat => ApiError::BadRequest("Invalid format"),
-            ValidationError::InvalidAmount => ApiError::BadRequest("Invalid amount"),
+            ValidationError::InvalidAmount => {
+                ApiError::BadRequest("Invalid amount, expected a positive decimal like 4.50")
+            }
+            ValidationError::InvalidLimit => {
+                ApiError::BadRequest("Limits must be positive whole amounts")
+            }
+            ValidationError::InvalidExchangeRate => ApiError::BadRequest(
+                "Invalid exchange rate, expected date,base,quote,rate with two different currencies and a positive rate",
+            ),
+            ValidationError::InvalidCurrency => {
+                ApiError::BadRequest("Invalid currency, expected an ISO 4217 code like EUR")
+            }
             ValidationError::InvalidCategory => ApiError::BadRequest("Invalid Category"),
+            ValidationError::InvalidScope => ApiError::BadRequest("Invalid scope"),
+            ValidationError::InvalidExpiry => ApiError::BadRequest("Invalid expiry"),
+            ValidationError::CommonPassword => ApiError::BadRequest("Password is too common"),
+            ValidationError::InvalidRole => ApiError::BadRequest("Invalid role"),
+            ValidationError::InvalidDate => {
+                ApiError::BadRequest("Invalid date, expected YYYY-MM-DD")
+            }
+            ValidationError::InvalidRange => {
+                ApiError::BadRequest("Range start must not be after its end")
+            }
+            ValidationError::InvalidSort => ApiError::BadRequest("Invalid sort"),
+            ValidationError::InvalidColor => {
+                ApiError::BadRequest("Invalid colour, expected #rrggbb")
+            }
+            ValidationError::InvalidIcon => ApiError::BadRequest("Invalid icon"),
+            ValidationError::InvalidTag => {
+                ApiError::BadRequest("Invalid tag, use up to 32 letters, digits, '-' or '_'")
+            }
+            ValidationError::TooManyTags => {
+                ApiError::BadRequest("An expense can have at most 20 tags")
+            }
+            ValidationError::TooManyAliases => {
+                ApiError::BadRequest("A merchant can have at most 20 aliases")
+            }
+            ValidationError::InvalidTime => ApiError::BadRequest("Invalid time, expected HH:MM"),
+            ValidationError::InvalidTimezone => {
+                ApiError::BadRequest("Invalid time zone, expected an IANA name like Europe/Berlin")
+            }
         }
     }
 }
@@ -80,14 +164,29 @@ impl From<DomainError> for ApiError {
 
 impl From<sqlx::Error> for ApiError {
     fn from(err: sqlx::Error) -> Self {
-        if let sqlx::Error::Database(db_err) = &err {
-            if db_err.message().contains("UNIQUE constraint failed") {
-                return ApiError::Conflict("Username already exists");
-            }
+        if let sqlx::Error::Database(db_err) = &err
+            && let Some(columns) = db_err.message().strip_prefix("UNIQUE constraint failed: ")
+        {
+            return ApiError::Conflict(unique_conflict(columns));
         }
         ApiError::Internal
     }
 }
+
+/// Names what clashed, from the `table.column, ...` list SQLite reports for a
+/// violated UNIQUE constraint.
+fn unique_conflict(columns: &str) -> &'static str {
+    match columns {
+        "users.username" => "Username already exists",
+        "categories.user_id, categories.name" => "A category with that name already exists",
+        "tags.user_id, tags.name" => "A tag with that name already exists",
+        "merchants.user_id, merchants.name" => "A merchant with that name already exists",
+        "merchant_aliases.user_id, merchant_aliases.alias" => {
+            "Another merchant already has that alias"
+        }
+        _ => "Conflicts with an existing record",
+    }
+}
 impl From<std::env::VarError> for ApiError {
     fn from(_: std::env::VarError) -> Self {
         ApiError::Internal
@@ -103,8 +202,90 @@ impl From<http::header::ToStrError> for ApiError {
         ApiError::Internal
     }
 }
+impl From<http::header::InvalidHeaderValue> for ApiError {
+    fn from(_: http::header::InvalidHeaderValue) -> Self {
+        ApiError::Internal
+    }
+}
 impl From<std::num::ParseIntError> for ApiError {
     fn from(_: std::num::ParseIntError) -> Self {
         ApiError::Internal
     }
 }
+
+impl From<LimitBreach> for ApiError {
+    fn from(breach: LimitBreach) -> Self {
+        ApiError::AmountLimit(breach)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    /// The error SQLite raises for inserting the same row into `table` twice.
+    async fn duplicate_error(pool: &SqlitePool, table: &str, columns: &str) -> ApiError {
+        let insert = format!("INSERT INTO {table} ({columns}) VALUES (1, 'x')");
+        sqlx::query(&insert).execute(pool).await.unwrap();
+        sqlx::query(&insert).execute(pool).await.unwrap_err().into()
+    }
+
+    #[tokio::test]
+    async fn unique_violations_name_what_clashed() {
+        // Each in-memory connection is its own database, so keep to one.
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE users (id INTEGER, username TEXT UNIQUE);
+            CREATE TABLE categories (user_id INTEGER, name TEXT, UNIQUE (user_id, name));
+            CREATE TABLE tags (user_id INTEGER, name TEXT, UNIQUE (user_id, name));
+            CREATE TABLE merchants (user_id INTEGER, name TEXT, UNIQUE (user_id, name));
+            CREATE TABLE merchant_aliases (user_id INTEGER, alias TEXT, UNIQUE (user_id, alias));
+            CREATE TABLE sessions (id INTEGER, token_hash TEXT UNIQUE);
+        "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let expected = [
+            ("users", "id, username", "Username already exists"),
+            (
+                "categories",
+                "user_id, name",
+                "A category with that name already exists",
+            ),
+            (
+                "tags",
+                "user_id, name",
+                "A tag with that name already exists",
+            ),
+            (
+                "merchants",
+                "user_id, name",
+                "A merchant with that name already exists",
+            ),
+            (
+                "merchant_aliases",
+                "user_id, alias",
+                "Another merchant already has that alias",
+            ),
+            (
+                "sessions",
+                "id, token_hash",
+                "Conflicts with an existing record",
+            ),
+        ];
+        for (table, columns, message) in expected {
+            match duplicate_error(&pool, table, columns).await {
+                ApiError::Conflict(got) => assert_eq!(got, message, "{table}"),
+                other => panic!("{table}: expected a conflict, got {other}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::api::dto::ApiError;
+use crate::domain::errors::ValidationError;
+use crate::domain::{NewApiToken, Scope, TokenName};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+#[derive(Deserialize)]
+pub struct CreateApiTokenRequest {
+    name: String,
+    scopes: Vec<String>,
+    expires_in_days: Option<i64>,
+}
+
+impl TryFrom<CreateApiTokenRequest> for NewApiToken {
+    type Error = ApiError;
+    fn try_from(input: CreateApiTokenRequest) -> Result<Self, Self::Error> {
+        let name = TokenName::try_from(input.name)?;
+
+        let mut scopes: Vec<Scope> = Vec::new();
+        for raw in &input.scopes {
+            let scope = Scope::try_from(raw.as_str())?;
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        if scopes.is_empty() {
+            return Err(ValidationError::InvalidScope.into());
+        }
+
+        if input
+            .expires_in_days
+            .is_some_and(|days| !(1..=365).contains(&days))
+        {
+            return Err(ValidationError::InvalidExpiry.into());
+        }
+
+        Ok(Self {
+            name,
+            scopes,
+            expires_in_days: input.expires_in_days,
+        })
+    }
+}
+
+#[derive(FromRow)]
+pub struct ApiTokenDbRow {
+    id: i64,
+    name: String,
+    scopes: String,
+    created_at: String,
+    expires_at: Option<String>,
+    last_used_at: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ApiTokenResponseDTO {
+    id: i64,
+    name: String,
+    scopes: Vec<String>,
+    created_at: String,
+    expires_at: Option<String>,
+    last_used_at: Option<String>,
+}
+
+impl From<ApiTokenDbRow> for ApiTokenResponseDTO {
+    fn from(row: ApiTokenDbRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            scopes: row.scopes.split_whitespace().map(String::from).collect(),
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+            last_used_at: row.last_used_at,
+        }
+    }
+}
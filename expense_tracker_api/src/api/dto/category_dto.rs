@@ -0,0 +1,117 @@
+use crate::api::dto::ApiError;
+use crate::domain::{Category, CategoryChanges, CategoryColor, CategoryIcon, NewCategory};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::prelude::FromRow;
+
+#[derive(Serialize, FromRow)]
+pub struct CategoryResponseDTO {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    /// e.g. `Food > Coffee`.
+    pub path: String,
+    /// 1 for top-level categories.
+    pub depth: i64,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    /// Expenses filed directly under this category, not its subcategories.
+    pub expense_count: i64,
+    pub created_at: String,
+}
+
+/// Spending in a category in minor units of the base currency. The `own_`
+/// fields cover expenses filed directly under it; `total` and `expense_count`
+/// also include all its subcategories.
+#[derive(Serialize, FromRow)]
+pub struct CategoryTotalDTO {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub own_total: i64,
+    pub own_count: i64,
+    pub total: i64,
+    pub expense_count: i64,
+}
+
+#[derive(Deserialize)]
+pub struct NewCategoryRequestDTO {
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// Omitted fields are left alone; an empty `color` or `icon` clears it, and
+/// a `null` `parent_id` makes the category top-level.
+#[derive(Deserialize)]
+pub struct UpdateCategoryRequestDTO {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub parent_id: Option<Option<i64>>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MergeCategoryRequestDTO {
+    /// Category that takes over the expenses.
+    pub into: i64,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteCategoryQuery {
+    /// Category to move the deleted one's expenses to.
+    pub reassign_to: Option<i64>,
+}
+
+impl TryFrom<NewCategoryRequestDTO> for NewCategory {
+    type Error = ApiError;
+    fn try_from(input: NewCategoryRequestDTO) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: Category::try_from(input.name)?,
+            parent_id: input.parent_id,
+            color: input
+                .color
+                .as_deref()
+                .map(CategoryColor::try_from)
+                .transpose()?,
+            icon: input
+                .icon
+                .as_deref()
+                .map(CategoryIcon::try_from)
+                .transpose()?,
+        })
+    }
+}
+
+impl TryFrom<UpdateCategoryRequestDTO> for CategoryChanges {
+    type Error = ApiError;
+    fn try_from(input: UpdateCategoryRequestDTO) -> Result<Self, Self::Error> {
+        let color = match input.color.as_deref().map(str::trim) {
+            None => None,
+            Some("") => Some(None),
+            Some(color) => Some(Some(CategoryColor::try_from(color)?)),
+        };
+        let icon = match input.icon.as_deref().map(str::trim) {
+            None => None,
+            Some("") => Some(None),
+            Some(icon) => Some(Some(CategoryIcon::try_from(icon)?)),
+        };
+
+        Ok(Self {
+            name: input.name.map(Category::try_from).transpose()?,
+            parent_id: input.parent_id,
+            color,
+            icon,
+        })
+    }
+}
+
+/// Tells a field sent as `null` (`Some(None)`) apart from one left out (`None`).
+pub fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -1,8 +1,57 @@
+use crate::api::dto::ApiError;
+use crate::api::pagination::Position;
+use crate::domain::user_types::Role;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String,
+    /// Login session the token belongs to; `None` for personal access tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    /// Tokens issued before roles existed decode as `Role::User`.
+    #[serde(default)]
+    pub role: Role,
+    pub exp: i64,
+    pub iat: i64,
+}
+
+impl Claims {
+    pub fn session_id(&self) -> Result<&str, ApiError> {
+        self.sid
+            .as_deref()
+            .ok_or(ApiError::Forbidden("this action requires a login session"))
+    }
+}
+
+/// Short-lived proof that the password step of a two-factor login succeeded.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChallengeClaims {
+    pub sub: String,
+    pub aud: String,
+    pub exp: i64,
+    pub iat: i64,
+}
+
+/// Signed link sent by email; only valid while the account still has `email`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EmailClaims {
+    pub sub: String,
+    pub email: String,
+    pub aud: String,
+    pub exp: i64,
+    pub iat: i64,
+}
+
+/// Position after the last row of a page, handed out as an opaque cursor.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CursorClaims {
+    pub sub: String,
+    pub aud: String,
+    #[serde(flatten)]
+    pub position: Position,
+    /// Fingerprint of the query the cursor belongs to.
+    pub query: String,
     pub exp: i64,
     pub iat: i64,
 }
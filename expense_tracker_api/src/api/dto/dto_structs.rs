@@ -1,3 +1,4 @@
+use crate::api::dto::{AmountInput, category_dto::present};
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 #[derive(Deserialize)]
@@ -5,28 +6,116 @@ pub struct LoginRequestDTO {
     pub username: String,
     pub password: String,
 }
+#[derive(Deserialize)]
+pub struct RefreshRequestDTO {
+    pub refresh_token: String,
+}
+#[derive(Deserialize)]
+pub struct ChangePasswordRequestDTO {
+    pub old_password: String,
+    pub new_password: String,
+}
+#[derive(Deserialize)]
+pub struct ForgotPasswordRequestDTO {
+    pub email: String,
+}
+#[derive(Deserialize)]
+pub struct ResetPasswordRequestDTO {
+    pub token: String,
+    pub new_password: String,
+}
+#[derive(Deserialize)]
+pub struct TotpCodeRequestDTO {
+    pub code: String,
+}
+#[derive(Deserialize)]
+pub struct TwoFactorLoginRequestDTO {
+    pub challenge_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+/// Omitted fields are left unchanged. Changing the email needs the current password.
+#[derive(Deserialize)]
+pub struct UpdateProfileRequestDTO {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub current_password: Option<String>,
+    /// IANA name, e.g. `Europe/Berlin`.
+    pub timezone: Option<String>,
+    /// ISO 4217 code; converts every existing expense too.
+    pub base_currency: Option<String>,
+    /// Whole units of the base currency; `null` falls back to the default.
+    #[serde(default, deserialize_with = "present")]
+    pub max_expense_amount: Option<Option<i64>>,
+    /// Whole units of the base currency; `null` falls back to the default.
+    #[serde(default, deserialize_with = "present")]
+    pub confirm_above: Option<Option<i64>>,
+}
+#[derive(Deserialize)]
+pub struct DeleteAccountRequestDTO {
+    pub password: String,
+}
 #[derive(Serialize, FromRow)]
 pub struct UserResponseDTO {
     pub id: i64,
     pub username: String,
     pub email: String,
+    pub email_verified_at: Option<String>,
+    /// IANA name new expenses are dated in by default.
+    pub timezone: String,
+    /// ISO 4217 code totals are reported in.
+    pub base_currency: String,
+    /// The user's own limits; `null` when the default applies.
+    pub max_expense_amount: Option<i64>,
+    pub confirm_above: Option<i64>,
     pub created_at: String,
 }
+/// A login session as listed on `GET /me/sessions`.
 #[derive(Serialize, FromRow)]
-pub struct ExpenseResponseDTO {
-    pub id: i64,
-    pub expense_desc: String,
-    pub amount: i64,
-    pub category: String,
+pub struct SessionResponseDTO {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
     pub created_at: String,
+    pub last_seen_at: Option<String>,
+    /// True for the session the request itself was made with.
+    pub current: bool,
 }
 #[derive(Deserialize)]
 pub struct UpdateRequestDTO {
     pub expense_desc: Option<String>,
-    pub amount: Option<i64>,
+    /// An empty string clears it.
+    pub merchant: Option<String>,
+    /// An empty string clears it.
+    pub notes: Option<String>,
+    /// Decimal in `currency`, or the expense's current one, e.g. `"4.50"`.
+    pub amount: Option<AmountInput>,
+    /// ISO 4217 code; needs `amount` alongside.
+    pub currency: Option<String>,
+    /// Replaces all of the expense's tags.
+    pub tags: Option<Vec<String>>,
+    pub add_tags: Option<Vec<String>>,
+    pub remove_tags: Option<Vec<String>>,
+    /// `YYYY-MM-DD`.
+    pub occurred_on: Option<String>,
+    /// `HH:MM` or `HH:MM:SS`.
+    pub occurred_time: Option<String>,
+    /// IANA name, e.g. `Europe/Berlin`.
+    pub timezone: Option<String>,
+    /// Accepts a new amount above the user's confirmation threshold.
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailQuery {
+    pub token: String,
 }
 
+/// Parameters the identity provider redirects back with.
 #[derive(Deserialize)]
-pub struct QueryExpense {
-    pub search: Option<String>,
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
 }
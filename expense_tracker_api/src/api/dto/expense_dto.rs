@@ -1,29 +1,201 @@
-use crate::api::dto::ApiError;
-use crate::domain::{Amount, Category, Description, NewExpense};
+use crate::api::dto::{AmountInput, ApiError};
+use crate::domain::{
+    Category, Currency, DateRange, Description, ExpenseQuery, Merchant, Money, NewExpense, Notes,
+    SortField, SortOrder, Tag, TagMatch, UserSettings,
+    errors::ValidationError,
+    expense_types::{parse_date, parse_time},
+    user_types::Timezone,
+};
 use chrono::Utc;
 use serde::Deserialize;
 
 #[derive(Deserialize)]
 pub struct NewExpenseRequest {
     expense_desc: String,
-    amount: i64,
-    category: String,
+    merchant: Option<String>,
+    notes: Option<String>,
+    /// Decimal in `currency`, e.g. `"4.50"`.
+    amount: AmountInput,
+    /// ISO 4217 code; the user's base currency when omitted.
+    currency: Option<String>,
+    /// The merchant's default category when omitted.
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// `YYYY-MM-DD`; today in `timezone` when omitted.
+    occurred_on: Option<String>,
+    /// `HH:MM` or `HH:MM:SS`, wall-clock time in `timezone`.
+    occurred_time: Option<String>,
+    /// IANA name, e.g. `Europe/Berlin`; the user's own time zone when omitted.
+    timezone: Option<String>,
+    /// Accepts an amount above the user's confirmation threshold.
+    #[serde(default)]
+    confirm: bool,
 }
 
-impl TryFrom<NewExpenseRequest> for NewExpense {
-    type Error = ApiError;
-    fn try_from(input: NewExpenseRequest) -> Result<Self, Self::Error> {
+impl NewExpenseRequest {
+    /// Validates the request, filling what it leaves out from the user's `settings`.
+    pub fn into_new_expense(self, settings: &UserSettings) -> Result<NewExpense, ApiError> {
+        let input = self;
         let valid_description = Description::try_from(input.expense_desc.as_str())?;
-        let valid_amount = Amount::try_from(input.amount)?;
-        let category = Category::try_from(input.category)?;
+        let merchant = input
+            .merchant
+            .as_deref()
+            .map(Merchant::try_from)
+            .transpose()?;
+        let notes = input.notes.as_deref().map(Notes::try_from).transpose()?;
+        let currency = match input.currency.as_deref() {
+            Some(currency) => Currency::try_from(currency)?,
+            None => settings.base_currency,
+        };
+        let valid_amount = input.amount.parse(currency)?;
+        let category = input.category.map(Category::try_from).transpose()?;
+        let tags = Tag::parse_all(&input.tags)?;
+        let occurred_time = input.occurred_time.as_deref().map(parse_time).transpose()?;
+        let timezone = input
+            .timezone
+            .as_deref()
+            .map(Timezone::try_from)
+            .transpose()?
+            .unwrap_or(settings.timezone);
+        let occurred_on = match input.occurred_on.as_deref() {
+            Some(date) => parse_date(date)?,
+            None => timezone.today(),
+        };
 
         let created_at = Utc::now().to_rfc3339();
 
-        Ok(Self {
+        Ok(NewExpense {
             expense_desc: valid_description,
+            merchant,
+            notes,
             amount: valid_amount,
             category,
+            tags,
+            occurred_on,
+            occurred_time,
+            timezone,
+            confirmed: input.confirm,
             created_at,
         })
     }
 }
+
+#[derive(Deserialize)]
+pub struct QueryExpense {
+    pub search: Option<String>,
+    /// `next_cursor` from the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    /// `YYYY-MM-DD`, inclusive.
+    pub from: Option<String>,
+    /// `YYYY-MM-DD`, inclusive.
+    pub to: Option<String>,
+    /// Comma separated, e.g. `food,fare`.
+    pub category: Option<String>,
+    /// ISO 4217 code the expense was recorded in.
+    pub currency: Option<String>,
+    /// Decimal in the user's base currency, e.g. `4.50`.
+    pub min_amount: Option<String>,
+    /// Decimal in the user's base currency, e.g. `20`.
+    pub max_amount: Option<String>,
+    /// Comma separated, e.g. `business-trip,reimbursable`.
+    pub tags: Option<String>,
+    /// `any` (default) or `all` of `tags`.
+    pub tag_match: Option<String>,
+    /// `date`, `amount`, `category` or `relevance`; searches default to
+    /// `relevance`, everything else to `date`.
+    pub sort: Option<String>,
+    /// `asc` or `desc` (default).
+    pub order: Option<String>,
+}
+
+impl QueryExpense {
+    /// Validates the filters, reading amount bounds in `base_currency`.
+    pub fn to_query(&self, base_currency: Currency) -> Result<ExpenseQuery, ApiError> {
+        let input = self;
+        let search = input
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|search| !search.is_empty())
+            .map(String::from);
+
+        let dates = DateRange::parse(input.from.as_deref(), input.to.as_deref())?;
+
+        let categories = input
+            .category
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| Category::try_from(name.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let currency = input
+            .currency
+            .as_deref()
+            .map(Currency::try_from)
+            .transpose()?;
+        let parse_amount = |amount: &str| Money::parse(amount, base_currency);
+        let min_amount = input.min_amount.as_deref().map(parse_amount).transpose()?;
+        let max_amount = input.max_amount.as_deref().map(parse_amount).transpose()?;
+        if let (Some(min), Some(max)) = (&min_amount, &max_amount)
+            && min.minor_units() > max.minor_units()
+        {
+            return Err(ValidationError::InvalidRange.into());
+        }
+
+        let tags = Tag::parse_all(
+            &input
+                .tags
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .filter(|tag| !tag.trim().is_empty())
+                .collect::<Vec<_>>(),
+        )?;
+        let tag_match = input
+            .tag_match
+            .as_deref()
+            .map(TagMatch::try_from)
+            .transpose()?
+            .unwrap_or_default();
+
+        let sort = match input.sort.as_deref().map(SortField::try_from).transpose()? {
+            Some(SortField::Relevance) if search.is_none() => {
+                return Err(ValidationError::InvalidSort.into());
+            }
+            Some(sort) => sort,
+            None if search.is_some() => SortField::Relevance,
+            None => SortField::Date,
+        };
+        let order = input
+            .order
+            .as_deref()
+            .map(SortOrder::try_from)
+            .transpose()?
+            .unwrap_or(SortOrder::Desc);
+
+        Ok(ExpenseQuery {
+            search,
+            dates,
+            categories,
+            currency,
+            min_amount,
+            max_amount,
+            tags,
+            tag_match,
+            sort,
+            order,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExpenseSummaryQuery {
+    /// `YYYY-MM-DD`, inclusive.
+    pub from: Option<String>,
+    /// `YYYY-MM-DD`, inclusive.
+    pub to: Option<String>,
+}
@@ -1,14 +1,31 @@
-use crate::domain::{Amount, Category, Description};
-use crate::{api::dto::ApiError, domain::Expense};
+use crate::domain::{
+    Category, Currency, Description, Merchant, Money, Notes, Tag,
+    expense_types::{parse_date, parse_time},
+    user_types::Timezone,
+};
+use crate::{
+    api::dto::{ApiError, MoneyDTO},
+    domain::Expense,
+};
 use serde::Serialize;
 use sqlx::prelude::FromRow;
-#[derive(FromRow, Serialize)]
+#[derive(Serialize)]
 pub struct ExpenseRow {
     id: i64,
     expense_desc: String,
-    amount: i64,
+    merchant: Option<String>,
+    notes: Option<String>,
+    amount: MoneyDTO,
+    /// `amount` in the user's base currency.
+    base_amount: MoneyDTO,
+    exchange_rate: f64,
     category: String,
+    tags: Vec<String>,
+    occurred_on: String,
+    occurred_time: Option<String>,
+    timezone: String,
     created_at: String,
+    updated_at: Option<String>,
 }
 
 impl TryFrom<Expense> for ExpenseRow {
@@ -17,9 +34,20 @@ impl TryFrom<Expense> for ExpenseRow {
         Ok(Self {
             id: value.id,
             expense_desc: value.expense_desc.into_inner(),
-            amount: value.amount.as_i64(),
+            merchant: value.merchant.map(Merchant::into_inner),
+            notes: value.notes.map(Notes::into_inner),
+            amount: value.amount.into(),
+            base_amount: value.base_amount.into(),
+            exchange_rate: value.exchange_rate,
             category: value.category.into_inner(),
+            tags: value.tags.into_iter().map(Tag::into_inner).collect(),
+            occurred_on: value.occurred_on.to_string(),
+            occurred_time: value
+                .occurred_time
+                .map(|time| time.format("%H:%M:%S").to_string()),
+            timezone: value.timezone.name().to_string(),
             created_at: value.created_at,
+            updated_at: value.updated_at,
         })
     }
 }
@@ -27,20 +55,82 @@ impl TryFrom<Expense> for ExpenseRow {
 pub struct ExpenseDbRow {
     id: i64,
     expense_desc: String,
+    merchant: Option<String>,
+    notes: Option<String>,
+    /// In minor units of `currency`.
     amount: i64,
+    currency: String,
+    base_amount: i64,
+    base_currency: String,
+    exchange_rate: f64,
     category: String,
+    /// Comma separated, from `group_concat`.
+    tags: Option<String>,
+    occurred_on: String,
+    occurred_time: Option<String>,
+    timezone: Option<String>,
     created_at: String,
+    updated_at: Option<String>,
 }
 impl TryFrom<ExpenseDbRow> for Expense {
     type Error = ApiError;
 
     fn try_from(row: ExpenseDbRow) -> Result<Self, Self::Error> {
+        let mut tags = row
+            .tags
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|tag| !tag.is_empty())
+            .map(Tag::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        tags.sort();
+
         Ok(Expense {
             id: row.id,
             expense_desc: Description::try_from(row.expense_desc.as_str())?,
-            amount: Amount::try_from(row.amount)?,
+            merchant: row
+                .merchant
+                .as_deref()
+                .map(Merchant::try_from)
+                .transpose()?,
+            notes: row.notes.as_deref().map(Notes::try_from).transpose()?,
+            amount: Money::new(row.amount, Currency::try_from(row.currency.as_str())?)?,
+            base_amount: Money::from_minor_units(
+                row.base_amount,
+                Currency::try_from(row.base_currency.as_str())?,
+            ),
+            exchange_rate: row.exchange_rate,
             category: Category::try_from(row.category)?,
+            tags,
+            occurred_on: parse_date(&row.occurred_on)?,
+            occurred_time: row.occurred_time.as_deref().map(parse_time).transpose()?,
+            timezone: row
+                .timezone
+                .as_deref()
+                .map(Timezone::try_from)
+                .transpose()?
+                .unwrap_or_default(),
             created_at: row.created_at,
+            updated_at: row.updated_at,
         })
     }
 }
+
+/// A listed expense with its search highlight, `None` when the listing was
+/// not a search.
+#[derive(FromRow)]
+pub struct ListedExpenseDbRow {
+    #[sqlx(flatten)]
+    pub expense: ExpenseDbRow,
+    pub snippet: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ListedExpenseRow {
+    #[serde(flatten)]
+    pub expense: ExpenseRow,
+    /// Matched text wrapped in `<mark>` tags.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
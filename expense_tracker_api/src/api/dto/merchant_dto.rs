@@ -0,0 +1,108 @@
+use crate::api::dto::{ApiError, category_dto::present};
+use crate::domain::{Merchant, MerchantChanges, NewMerchant};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+/// Separates aliases in `MerchantDbRow::aliases`; control characters never
+/// appear in a merchant name.
+pub const ALIAS_SEPARATOR: char = '\u{1f}';
+
+#[derive(Serialize)]
+pub struct MerchantResponseDTO {
+    pub id: i64,
+    pub name: String,
+    /// Category new expenses at this merchant are filed under by default.
+    pub category_id: Option<i64>,
+    pub category: Option<String>,
+    pub aliases: Vec<String>,
+    pub expense_count: i64,
+    pub created_at: String,
+}
+
+#[derive(FromRow)]
+pub struct MerchantDbRow {
+    id: i64,
+    name: String,
+    category_id: Option<i64>,
+    category: Option<String>,
+    /// Joined with `ALIAS_SEPARATOR`, from `group_concat`.
+    aliases: Option<String>,
+    expense_count: i64,
+    created_at: String,
+}
+
+impl From<MerchantDbRow> for MerchantResponseDTO {
+    fn from(row: MerchantDbRow) -> Self {
+        let mut aliases: Vec<String> = row
+            .aliases
+            .as_deref()
+            .unwrap_or_default()
+            .split(ALIAS_SEPARATOR)
+            .filter(|alias| !alias.is_empty())
+            .map(String::from)
+            .collect();
+        aliases.sort_by_key(|alias| alias.to_lowercase());
+
+        Self {
+            id: row.id,
+            name: row.name,
+            category_id: row.category_id,
+            category: row.category,
+            aliases,
+            expense_count: row.expense_count,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NewMerchantRequestDTO {
+    pub name: String,
+    pub category_id: Option<i64>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// Omitted fields are left alone; a `null` `category_id` removes the default
+/// category and `aliases` replaces the whole list.
+#[derive(Deserialize)]
+pub struct UpdateMerchantRequestDTO {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub category_id: Option<Option<i64>>,
+    pub aliases: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct MerchantQuery {
+    /// Start of a merchant name or alias, for autocomplete.
+    pub prefix: Option<String>,
+    /// Defaults to 20, at most 100.
+    pub limit: Option<i64>,
+}
+
+impl TryFrom<NewMerchantRequestDTO> for NewMerchant {
+    type Error = ApiError;
+    fn try_from(input: NewMerchantRequestDTO) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: Merchant::try_from(input.name.as_str())?,
+            category_id: input.category_id,
+            aliases: Merchant::parse_all(&input.aliases)?,
+        })
+    }
+}
+
+impl TryFrom<UpdateMerchantRequestDTO> for MerchantChanges {
+    type Error = ApiError;
+    fn try_from(input: UpdateMerchantRequestDTO) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: input.name.as_deref().map(Merchant::try_from).transpose()?,
+            category_id: input.category_id,
+            aliases: input
+                .aliases
+                .as_deref()
+                .map(Merchant::parse_all)
+                .transpose()?,
+        })
+    }
+}
@@ -1,15 +1,39 @@
+pub mod admin_dto;
 pub mod api_errors;
+pub mod api_token_dto;
+pub mod category_dto;
 pub mod claims;
 pub mod dto_structs;
 pub mod expense_dto;
 pub mod list_expense_response;
+pub mod merchant_dto;
+pub mod money_dto;
+pub mod tag_dto;
 pub mod user_dto;
 
+pub use admin_dto::{
+    AdminUserResponseDTO, AppSettingsDTO, AuditLogDbRow, AuditLogEntryDTO, ExchangeRatesRequestDTO,
+    SetRoleRequestDTO, UpdateAppSettingsRequestDTO,
+};
 pub use api_errors::ApiError;
-pub use claims::Claims;
+pub use api_token_dto::{ApiTokenDbRow, ApiTokenResponseDTO, CreateApiTokenRequest};
+pub use category_dto::{
+    CategoryResponseDTO, CategoryTotalDTO, DeleteCategoryQuery, MergeCategoryRequestDTO,
+    NewCategoryRequestDTO, UpdateCategoryRequestDTO,
+};
+pub use claims::{ChallengeClaims, Claims, CursorClaims, EmailClaims};
 pub use dto_structs::{
-    ExpenseResponseDTO, LoginRequestDTO, QueryExpense, UpdateRequestDTO, UserResponseDTO,
+    ChangePasswordRequestDTO, DeleteAccountRequestDTO, ForgotPasswordRequestDTO, LoginRequestDTO,
+    OidcCallbackQuery, RefreshRequestDTO, ResetPasswordRequestDTO, SessionResponseDTO,
+    TotpCodeRequestDTO, TwoFactorLoginRequestDTO, UpdateProfileRequestDTO, UpdateRequestDTO,
+    UserResponseDTO, VerifyEmailQuery,
+};
+pub use expense_dto::{ExpenseSummaryQuery, NewExpenseRequest, QueryExpense};
+pub use list_expense_response::{ExpenseDbRow, ExpenseRow, ListedExpenseDbRow, ListedExpenseRow};
+pub use merchant_dto::{
+    MerchantDbRow, MerchantQuery, MerchantResponseDTO, NewMerchantRequestDTO,
+    UpdateMerchantRequestDTO,
 };
-pub use expense_dto::NewExpenseRequest;
-pub use list_expense_response::{ExpenseDbRow, ExpenseRow};
-pub use user_dto::CreateUserRequestDTO;
+pub use money_dto::{AmountInput, MoneyDTO};
+pub use tag_dto::TagTotalDTO;
+pub use user_dto::{CreateUserRequestDTO, password_hasher};
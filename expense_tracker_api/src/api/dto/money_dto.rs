@@ -0,0 +1,93 @@
+use crate::domain::{Currency, Money, errors::ValidationError};
+use serde::{Deserialize, Serialize};
+
+/// An amount as sent back to clients: exact minor units for arithmetic and
+/// a decimal string for display.
+#[derive(Serialize)]
+pub struct MoneyDTO {
+    /// e.g. `450` for 4.50 USD.
+    pub minor_units: i64,
+    pub currency: &'static str,
+    /// e.g. `4.50`.
+    pub formatted: String,
+}
+
+/// An amount as clients send it: a decimal string such as `"4.50"`, or a
+/// plain JSON number in major units.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum AmountInput {
+    Text(String),
+    Number(serde_json::Number),
+}
+
+impl MoneyDTO {
+    pub fn new(minor_units: i64, currency: Currency) -> Self {
+        Self {
+            minor_units,
+            currency: currency.code(),
+            formatted: currency.format(minor_units),
+        }
+    }
+}
+
+impl From<Money> for MoneyDTO {
+    fn from(money: Money) -> Self {
+        Self::new(money.minor_units(), money.currency())
+    }
+}
+
+impl AmountInput {
+    pub fn parse(&self, currency: Currency) -> Result<Money, ValidationError> {
+        match self {
+            AmountInput::Text(text) => Money::parse(text, currency),
+            AmountInput::Number(number) => {
+                // A JSON number carries no precision of its own and floats
+                // print as `10.0`, so trailing zeros in the fraction are not
+                // decimal places the client asked for.
+                let text = number.to_string();
+                let text = if text.contains('.') {
+                    text.trim_end_matches('0').trim_end_matches('.')
+                } else {
+                    &text
+                };
+                Money::parse(text, currency)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minor_units(json: &str, code: &str) -> Option<i64> {
+        let input: AmountInput = serde_json::from_str(json).unwrap();
+        input
+            .parse(Currency::try_from(code).unwrap())
+            .ok()
+            .map(|money| money.minor_units())
+    }
+
+    #[test]
+    fn numbers_ignore_zeros_past_the_currency_places() {
+        assert_eq!(minor_units("10.0", "JPY"), Some(10));
+        assert_eq!(minor_units("10.00", "JPY"), Some(10));
+        assert_eq!(minor_units("100", "JPY"), Some(100));
+        assert_eq!(minor_units("100.0", "JPY"), Some(100));
+        assert_eq!(minor_units("4.50", "USD"), Some(450));
+        assert_eq!(minor_units("1.230", "BHD"), Some(1230));
+    }
+
+    #[test]
+    fn numbers_with_real_extra_places_are_rejected() {
+        assert_eq!(minor_units("10.5", "JPY"), None);
+        assert_eq!(minor_units("4.505", "USD"), None);
+    }
+
+    #[test]
+    fn strings_keep_their_written_precision() {
+        assert_eq!(minor_units(r#""4.50""#, "USD"), Some(450));
+        assert_eq!(minor_units(r#""10.0""#, "JPY"), None);
+    }
+}
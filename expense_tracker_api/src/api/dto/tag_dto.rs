@@ -0,0 +1,10 @@
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+
+#[derive(Serialize, FromRow)]
+pub struct TagTotalDTO {
+    pub name: String,
+    pub expense_count: i64,
+    /// In minor units of the user's base currency.
+    pub total: i64,
+}
@@ -1,7 +1,7 @@
 use crate::api::dto::api_errors::ApiError;
 use crate::domain::{
     NewUser,
-    user_types::{Email, Password, UserName},
+    user_types::{Email, Password, PasswordPolicy, UserName},
 };
 use argon2::{
     Argon2, PasswordHasher,
@@ -15,16 +15,20 @@ pub struct CreateUserRequestDTO {
     pub email: String,
     pub password: String,
 }
-impl TryFrom<CreateUserRequestDTO> for NewUser {
-    type Error = ApiError;
-    fn try_from(input: CreateUserRequestDTO) -> Result<Self, Self::Error> {
-        let valid_username = UserName::try_from(input.username)?;
-        let valid_email = Email::try_from(input.email)?;
-        let valid_password = Password::try_from(input.password)?;
+impl CreateUserRequestDTO {
+    /// Validates the request, checking the password against `policy`, and hashes the password.
+    pub fn into_new_user(
+        self,
+        policy: &PasswordPolicy,
+        argon2: &Argon2<'_>,
+    ) -> Result<NewUser, ApiError> {
+        let valid_username = UserName::try_from(self.username)?;
+        let valid_email = Email::try_from(self.email)?;
+        let valid_password = policy.validate(self.password)?;
 
-        let hashed_password = password_hasher(valid_password)?;
+        let hashed_password = password_hasher(argon2, valid_password)?;
 
-        Ok(Self {
+        Ok(NewUser {
             username: valid_username,
             email: valid_email,
             password_hash: hashed_password,
@@ -32,9 +36,8 @@ impl TryFrom<CreateUserRequestDTO> for NewUser {
     }
 }
 
-fn password_hasher(input: Password) -> Result<Password, ApiError> {
+pub fn password_hasher(argon2: &Argon2<'_>, input: Password) -> Result<Password, ApiError> {
     let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
 
     let password_hash = argon2
         .hash_password(input.as_str().as_bytes(), &salt)
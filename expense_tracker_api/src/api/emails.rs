@@ -0,0 +1,69 @@
+use crate::api::{
+    AppState,
+    dto::ApiError,
+    tokens::{
+        CHANGE_EMAIL_AUDIENCE, EMAIL_TOKEN_TTL_HOURS, VERIFY_EMAIL_AUDIENCE, issue_email_token,
+    },
+};
+use crate::mailer::{OutgoingEmail, deliver};
+
+/// Mails a signed verification link for `email`. Delivery failures are logged,
+/// not returned, so they never fail the request that triggered them.
+pub async fn send_verification_email(
+    state: &AppState,
+    user_id: i64,
+    username: &str,
+    email: &str,
+) -> Result<(), ApiError> {
+    let token = issue_email_token(&state.keyring, user_id, email, VERIFY_EMAIL_AUDIENCE)?;
+
+    let mail = OutgoingEmail {
+        to: email.to_string(),
+        subject: "Verify your expense tracker email".to_string(),
+        body: format!(
+            "Hi {username},\n\nConfirm this address by opening:\n\n{}/users/verify?token={token}\n\nThe link expires in {EMAIL_TOKEN_TTL_HOURS} hours.",
+            state.public_url
+        ),
+    };
+    if let Err(err) = deliver(state.mailer.clone(), mail).await {
+        tracing::error!(user_id, error = %err, "failed to send verification email");
+    }
+
+    Ok(())
+}
+
+/// Mails a confirmation link to `new_email` and a heads-up to `old_email`, so
+/// the owner notices a change they did not ask for.
+pub async fn send_email_change_emails(
+    state: &AppState,
+    user_id: i64,
+    username: &str,
+    old_email: &str,
+    new_email: &str,
+) -> Result<(), ApiError> {
+    let token = issue_email_token(&state.keyring, user_id, new_email, CHANGE_EMAIL_AUDIENCE)?;
+
+    let confirm = OutgoingEmail {
+        to: new_email.to_string(),
+        subject: "Confirm your new expense tracker email".to_string(),
+        body: format!(
+            "Hi {username},\n\nConfirm {new_email} as your new address by opening:\n\n{}/me/email/confirm?token={token}\n\nThe link expires in {EMAIL_TOKEN_TTL_HOURS} hours.",
+            state.public_url
+        ),
+    };
+    let notice = OutgoingEmail {
+        to: old_email.to_string(),
+        subject: "Your expense tracker email is being changed".to_string(),
+        body: format!(
+            "Hi {username},\n\nSomeone asked to change the email of your account to {new_email}. If this was not you, change your password now."
+        ),
+    };
+
+    for mail in [confirm, notice] {
+        if let Err(err) = deliver(state.mailer.clone(), mail).await {
+            tracing::error!(user_id, error = %err, "failed to send email change message");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use crate::api::dto::ApiError;
+use crate::domain::{Currency, ExchangeRate, Money, expense_types::parse_date};
+use chrono::NaiveDate;
+use sqlx::SqliteConnection;
+
+/// Units of `to` per unit of `from` on `date`: the latest rate published on
+/// or before it, read either way round.
+pub async fn rate<'e, E>(
+    executor: E,
+    from: Currency,
+    to: Currency,
+    date: NaiveDate,
+) -> Result<f64, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    if from == to {
+        return Ok(1.0);
+    }
+
+    let rate: Option<f64> = sqlx::query_scalar(
+        r#"
+        SELECT rate FROM (
+            SELECT rate_on, rate FROM exchange_rates
+            WHERE base = ?1 AND quote = ?2 AND rate_on <= ?3
+            UNION ALL
+            SELECT rate_on, 1.0 / rate FROM exchange_rates
+            WHERE base = ?2 AND quote = ?1 AND rate_on <= ?3
+        )
+        ORDER BY rate_on DESC
+        LIMIT 1
+    "#,
+    )
+    .bind(from.code())
+    .bind(to.code())
+    .bind(date.to_string())
+    .fetch_optional(executor)
+    .await?;
+
+    rate.ok_or(ApiError::BadRequest(
+        "No exchange rate to the base currency on or before the expense date",
+    ))
+}
+
+/// Adds or replaces rates; returns how many were written.
+pub async fn import(conn: &mut SqliteConnection, rates: &[ExchangeRate]) -> Result<u64, ApiError> {
+    let mut written = 0;
+    for rate in rates {
+        written += sqlx::query(
+            r#"
+            INSERT INTO exchange_rates (base, quote, rate_on, rate)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (base, quote, rate_on) DO UPDATE SET rate = excluded.rate
+        "#,
+        )
+        .bind(rate.base.code())
+        .bind(rate.quote.code())
+        .bind(rate.date.to_string())
+        .bind(rate.rate)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    }
+    Ok(written)
+}
+
+/// Converts an expense into `base` at the rate of the day it occurred and
+/// stores the result, so later rate uploads leave it untouched.
+pub async fn freeze(
+    conn: &mut SqliteConnection,
+    expense_id: i64,
+    base: Currency,
+) -> Result<(), ApiError> {
+    let (amount, currency, occurred_on): (i64, String, String) = sqlx::query_as(
+        r#"
+        SELECT amount, currency, occurred_on FROM expenses WHERE id = ?1
+    "#,
+    )
+    .bind(expense_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let amount = Money::from_minor_units(amount, Currency::try_from(currency.as_str())?);
+    let rate = rate(
+        &mut *conn,
+        amount.currency(),
+        base,
+        parse_date(&occurred_on)?,
+    )
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE expenses
+        SET base_amount = ?2, base_currency = ?3, exchange_rate = ?4
+        WHERE id = ?1
+    "#,
+    )
+    .bind(expense_id)
+    .bind(amount.convert(rate, base).minor_units())
+    .bind(base.code())
+    .bind(rate)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Re-converts every expense of the user into a new base currency.
+pub async fn freeze_all(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    base: Currency,
+) -> Result<(), ApiError> {
+    let ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM expenses WHERE user_id = ?1
+    "#,
+    )
+    .bind(user_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for id in ids {
+        freeze(conn, id, base).await?;
+    }
+    Ok(())
+}
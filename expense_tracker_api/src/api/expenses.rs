@@ -0,0 +1,59 @@
+use crate::api::dto::{ApiError, ExpenseDbRow};
+use crate::domain::{AmountLimits, Currency, Expense, UserSettings, user_types::Timezone};
+
+/// One of the user's expenses, tags included.
+pub async fn fetch<'e, E>(executor: E, user_id: i64, id: i64) -> Result<Expense, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let row: ExpenseDbRow = sqlx::query_as(
+        r#"
+        SELECT id, expense_desc, merchant, notes, amount, currency, base_amount, base_currency, exchange_rate,
+            category, occurred_on, occurred_time, timezone,
+            created_at, updated_at,
+            (
+                SELECT group_concat(t.name)
+                FROM expense_tags et
+                JOIN tags t ON t.id = et.tag_id
+                WHERE et.expense_id = expenses.id
+            ) AS tags
+        FROM expenses
+        WHERE id = ?1 AND user_id = ?2
+    "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or(ApiError::NotFound("expense not found"))?;
+
+    Expense::try_from(row)
+}
+
+/// The user's time zone, base currency and effective amount limits.
+pub async fn user_settings<'e, E>(executor: E, user_id: i64) -> Result<UserSettings, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let (timezone, base_currency, max, confirm_above): (String, String, Option<i64>, Option<i64>) =
+        sqlx::query_as(
+            r#"
+        SELECT u.timezone, u.base_currency,
+            COALESCE(u.max_expense_amount, s.max_expense_amount),
+            COALESCE(u.confirm_above, s.confirm_above)
+        FROM users u
+        CROSS JOIN app_settings s
+        WHERE u.id = ?1
+    "#,
+        )
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or(ApiError::NotFound("user not found"))?;
+
+    Ok(UserSettings {
+        timezone: Timezone::try_from(timezone.as_str())?,
+        base_currency: Currency::try_from(base_currency.as_str())?,
+        limits: AmountLimits { max, confirm_above },
+    })
+}
@@ -0,0 +1,36 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOrAuditor, RequireRole},
+    dto::{ApiError, AuditLogDbRow, AuditLogEntryDTO},
+};
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde_json::json;
+
+const AUDIT_LOG_PAGE: i64 = 200;
+
+/// Most recent admin actions first.
+pub async fn admin_audit_log(
+    RequireRole(claims, _): RequireRole<AdminOrAuditor>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let rows: Vec<AuditLogDbRow> = sqlx::query_as(
+        r#"
+        SELECT id, actor_id, action, target_user_id, details, created_at
+        FROM audit_log
+        ORDER BY id DESC
+        LIMIT ?1
+    "#,
+    )
+    .bind(AUDIT_LOG_PAGE)
+    .fetch_all(&state.pool)
+    .await?;
+
+    audit::record(&state.pool, actor_id, AuditAction::ViewAuditLog, None, None).await?;
+
+    let entries: Vec<AuditLogEntryDTO> = rows.into_iter().map(AuditLogEntryDTO::from).collect();
+
+    Ok((StatusCode::OK, Json(json!({"entries": entries}))))
+}
@@ -0,0 +1,63 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOnly, RequireRole},
+    dto::ApiError,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+/// Disables the account and ends all of its sessions. Personal access tokens
+/// are refused while the account stays disabled.
+pub async fn admin_disable_user(
+    RequireRole(claims, _): RequireRole<AdminOnly>,
+    State(state): State<AppState>,
+    Path(user_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+    if user_id == actor_id {
+        return Err(ApiError::BadRequest("You cannot disable your own account"));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE users
+        SET disabled_at = COALESCE(disabled_at, datetime('now'))
+        WHERE id = ?1
+    "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("User not found"));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE sessions
+        SET revoked_at = datetime('now')
+        WHERE user_id = ?1 AND revoked_at IS NULL
+    "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    audit::record(
+        &mut *tx,
+        actor_id,
+        AuditAction::DisableUser,
+        Some(user_id),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
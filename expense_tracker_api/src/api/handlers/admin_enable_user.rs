@@ -0,0 +1,46 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOnly, RequireRole},
+    dto::ApiError,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+pub async fn admin_enable_user(
+    RequireRole(claims, _): RequireRole<AdminOnly>,
+    State(state): State<AppState>,
+    Path(user_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+    let mut tx = state.pool.begin().await?;
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE users
+        SET disabled_at = NULL
+        WHERE id = ?1
+    "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("User not found"));
+    }
+
+    audit::record(
+        &mut *tx,
+        actor_id,
+        AuditAction::EnableUser,
+        Some(user_id),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
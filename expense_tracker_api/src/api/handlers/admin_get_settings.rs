@@ -0,0 +1,27 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOrAuditor, RequireRole},
+    dto::{ApiError, AppSettingsDTO},
+};
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde_json::json;
+
+pub async fn admin_get_settings(
+    RequireRole(claims, _): RequireRole<AdminOrAuditor>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let settings: AppSettingsDTO = sqlx::query_as(
+        r#"
+        SELECT max_expense_amount, confirm_above FROM app_settings WHERE id = 1
+    "#,
+    )
+    .fetch_one(&state.pool)
+    .await?;
+
+    audit::record(&state.pool, actor_id, AuditAction::ViewSettings, None, None).await?;
+
+    Ok((StatusCode::OK, Json(json!({"settings": settings}))))
+}
@@ -0,0 +1,52 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOnly, RequireRole},
+    dto::{ApiError, ExchangeRatesRequestDTO},
+    exchange_rates,
+};
+use crate::domain::ExchangeRate;
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Takes `{"rates": [...]}` as JSON, or `date,base,quote,rate` lines when
+/// sent as `text/csv`. Rates already frozen on expenses are not touched.
+pub async fn admin_import_exchange_rates(
+    RequireRole(claims, _): RequireRole<AdminOnly>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let is_csv = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/csv"));
+    let rates: Vec<ExchangeRate> = if is_csv {
+        ExchangeRate::parse_csv(&body)?
+    } else {
+        serde_json::from_str::<ExchangeRatesRequestDTO>(&body)
+            .map_err(|_| ApiError::BadRequest("Expected {\"rates\": [...]} or a text/csv body"))?
+            .try_into()?
+    };
+
+    let mut tx = state.pool.begin().await?;
+    let imported = exchange_rates::import(&mut tx, &rates).await?;
+    audit::record(
+        &mut *tx,
+        actor_id,
+        AuditAction::ImportExchangeRates,
+        None,
+        Some(json!({"imported": imported})),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({"imported": imported}))))
+}
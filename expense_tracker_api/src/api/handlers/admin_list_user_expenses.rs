@@ -0,0 +1,71 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOrAuditor, RequireRole},
+    dto::{ApiError, ExpenseDbRow, ExpenseRow},
+};
+use crate::domain::Expense;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn admin_list_user_expenses(
+    RequireRole(claims, _): RequireRole<AdminOrAuditor>,
+    State(state): State<AppState>,
+    Path(user_id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT 1 FROM users WHERE id = ?1
+    "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::NotFound("User not found"))?;
+
+    let rows: Vec<ExpenseDbRow> = sqlx::query_as(
+        r#"
+        SELECT id, expense_desc, merchant, notes, amount, currency, base_amount, base_currency, exchange_rate,
+            category, occurred_on, occurred_time, timezone,
+            created_at, updated_at,
+            (
+                SELECT group_concat(t.name)
+                FROM expense_tags et
+                JOIN tags t ON t.id = et.tag_id
+                WHERE et.expense_id = expenses.id
+            ) AS tags
+        FROM expenses
+        WHERE user_id = ?1
+        ORDER BY occurred_on DESC, id DESC
+    "#,
+    )
+    .bind(user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    audit::record(
+        &state.pool,
+        actor_id,
+        AuditAction::ViewExpenses,
+        Some(user_id),
+        None,
+    )
+    .await?;
+
+    let response_rows: Vec<ExpenseRow> = rows
+        .into_iter()
+        .map(Expense::try_from)
+        .collect::<Result<Vec<_>, ApiError>>()?
+        .into_iter()
+        .map(ExpenseRow::try_from)
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    Ok((StatusCode::OK, Json(json!({"Expenses": response_rows}))))
+}
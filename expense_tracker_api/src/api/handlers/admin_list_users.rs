@@ -0,0 +1,29 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOrAuditor, RequireRole},
+    dto::{AdminUserResponseDTO, ApiError},
+};
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde_json::json;
+
+pub async fn admin_list_users(
+    RequireRole(claims, _): RequireRole<AdminOrAuditor>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let users: Vec<AdminUserResponseDTO> = sqlx::query_as(
+        r#"
+        SELECT id, username, email, role, email_verified_at, disabled_at, created_at
+        FROM users
+        ORDER BY id
+    "#,
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    audit::record(&state.pool, actor_id, AuditAction::ListUsers, None, None).await?;
+
+    Ok((StatusCode::OK, Json(json!({"users": users}))))
+}
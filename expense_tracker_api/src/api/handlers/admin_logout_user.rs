@@ -0,0 +1,60 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOnly, RequireRole},
+    dto::ApiError,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Revokes every open session of the user, signing them out everywhere.
+pub async fn admin_logout_user(
+    RequireRole(claims, _): RequireRole<AdminOnly>,
+    State(state): State<AppState>,
+    Path(user_id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT 1 FROM users WHERE id = ?1
+    "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::NotFound("User not found"))?;
+
+    let revoked = sqlx::query(
+        r#"
+        UPDATE sessions
+        SET revoked_at = datetime('now')
+        WHERE user_id = ?1 AND revoked_at IS NULL
+    "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    audit::record(
+        &mut *tx,
+        actor_id,
+        AuditAction::ForceLogout,
+        Some(user_id),
+        Some(json!({"sessions_revoked": revoked})),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"msg": "Sessions revoked", "sessions_revoked": revoked})),
+    ))
+}
@@ -0,0 +1,64 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOnly, RequireRole},
+    dto::{AdminUserResponseDTO, ApiError, SetRoleRequestDTO},
+};
+use crate::domain::user_types::Role;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn admin_set_role(
+    RequireRole(claims, _): RequireRole<AdminOnly>,
+    State(state): State<AppState>,
+    Path(user_id): Path<i64>,
+    Json(payload): Json<SetRoleRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+    let role = Role::try_from(payload.role.as_str())?;
+    if user_id == actor_id {
+        return Err(ApiError::BadRequest("You cannot change your own role"));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let previous: String = sqlx::query_scalar(
+        r#"
+        SELECT role FROM users WHERE id = ?1
+    "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::NotFound("User not found"))?;
+
+    let user: AdminUserResponseDTO = sqlx::query_as(
+        r#"
+        UPDATE users
+        SET role = ?2
+        WHERE id = ?1
+        RETURNING id, username, email, role, email_verified_at, disabled_at, created_at
+    "#,
+    )
+    .bind(user_id)
+    .bind(role.as_str())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit::record(
+        &mut *tx,
+        actor_id,
+        AuditAction::ChangeRole,
+        Some(user_id),
+        Some(json!({"from": previous, "to": role.as_str()})),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({"user": user}))))
+}
@@ -0,0 +1,59 @@
+use crate::api::{
+    AppState,
+    audit::{self, AuditAction},
+    auth::{AdminOnly, RequireRole},
+    dto::{ApiError, AppSettingsDTO, UpdateAppSettingsRequestDTO},
+};
+use crate::domain::money::validate_limit;
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde_json::json;
+
+/// Changes the defaults for every user who has not set their own limits.
+pub async fn admin_update_settings(
+    RequireRole(claims, _): RequireRole<AdminOnly>,
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateAppSettingsRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let max_expense_amount = payload
+        .max_expense_amount
+        .map(|limit| limit.map(validate_limit).transpose())
+        .transpose()?;
+    let confirm_above = payload
+        .confirm_above
+        .map(|limit| limit.map(validate_limit).transpose())
+        .transpose()?;
+
+    let mut tx = state.pool.begin().await?;
+    let settings: AppSettingsDTO = sqlx::query_as(
+        r#"
+        UPDATE app_settings
+        SET max_expense_amount = CASE WHEN ?1 THEN ?2 ELSE max_expense_amount END,
+            confirm_above = CASE WHEN ?3 THEN ?4 ELSE confirm_above END
+        WHERE id = 1
+        RETURNING max_expense_amount, confirm_above
+    "#,
+    )
+    .bind(max_expense_amount.is_some())
+    .bind(max_expense_amount.flatten())
+    .bind(confirm_above.is_some())
+    .bind(confirm_above.flatten())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit::record(
+        &mut *tx,
+        actor_id,
+        AuditAction::UpdateSettings,
+        None,
+        Some(json!({
+            "max_expense_amount": settings.max_expense_amount,
+            "confirm_above": settings.confirm_above,
+        })),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({"settings": settings}))))
+}
@@ -0,0 +1,91 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, ChangePasswordRequestDTO, Claims},
+    passwords::{PasswordCheck, StoredPassword},
+    throttle::{self, Throttle},
+};
+use axum::{
+    Json,
+    extract::{ConnectInfo, Extension, State},
+    http::StatusCode,
+};
+use sqlx::prelude::FromRow;
+use std::net::SocketAddr;
+
+#[derive(FromRow)]
+struct PasswordOwner {
+    #[sqlx(flatten)]
+    password: StoredPassword,
+    username: String,
+}
+
+/// Guessing the current password here is throttled like a login, so a stolen
+/// access token cannot be used to brute-force it.
+pub async fn change_password(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(payload): Json<ChangePasswordRequestDTO>,
+) -> Result<StatusCode, ApiError> {
+    let sid = claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let owner: PasswordOwner = sqlx::query_as(
+        r#"
+            SELECT id, password_hash, password_legacy, username FROM users WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::NotFound("user not found"))?;
+
+    let ip = addr.ip().to_string();
+    let username_key = owner.username.to_ascii_lowercase();
+    throttle::ensure_allowed(&state.pool, Throttle::Ip, &ip).await?;
+    throttle::ensure_allowed(&state.pool, Throttle::Username, &username_key).await?;
+
+    let check = state
+        .password_hashing
+        .verify_and_upgrade(&state.pool, &owner.password, &payload.old_password)
+        .await?;
+    if check == PasswordCheck::Verified {
+        throttle::record_success(&state.pool, Throttle::Username, &username_key).await?;
+    } else {
+        throttle::record_failure(&state.pool, Throttle::Ip, &ip).await?;
+        throttle::record_failure(&state.pool, Throttle::Username, &username_key).await?;
+    }
+    check.ensure_verified()?;
+
+    let new_password = state.password_policy.validate(payload.new_password)?;
+    let new_hash = state.password_hashing.hash(new_password)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query(
+        r#"
+            UPDATE users SET password_hash = ?2, password_legacy = 0 WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .bind(new_hash.into_inner())
+    .execute(&mut *tx)
+    .await?;
+
+    // Every other login has to re-authenticate with the new password.
+    sqlx::query(
+        r#"
+            UPDATE sessions
+            SET revoked_at = datetime('now')
+            WHERE user_id = ?1 AND id != ?2 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(sid)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
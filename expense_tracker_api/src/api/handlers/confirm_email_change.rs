@@ -0,0 +1,46 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, VerifyEmailQuery},
+    tokens::{CHANGE_EMAIL_AUDIENCE, verify_email_token},
+};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn confirm_email_change(
+    State(state): State<AppState>,
+    Query(param): Query<VerifyEmailQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let claims = verify_email_token(&state.keyring, &param.token, CHANGE_EMAIL_AUDIENCE)?;
+    let user_id: i64 = claims
+        .sub
+        .parse()
+        .map_err(|_| ApiError::BadRequest("Invalid or expired token"))?;
+
+    // Matching the pending address makes each link single use and voids links
+    // for addresses requested before the latest one.
+    let changed = sqlx::query(
+        r#"
+            UPDATE users
+            SET email = pending_email,
+                pending_email = NULL,
+                email_verified_at = datetime('now'),
+                email_verified_legacy = 0
+            WHERE id = ?1 AND pending_email = ?2
+        "#,
+    )
+    .bind(user_id)
+    .bind(&claims.email)
+    .execute(&state.pool)
+    .await?;
+
+    if changed.rows_affected() == 0 {
+        return Err(ApiError::BadRequest("Invalid or expired token"));
+    }
+
+    Ok((StatusCode::OK, Json(json!({"msg": "Email changed"}))))
+}
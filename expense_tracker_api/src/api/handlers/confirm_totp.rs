@@ -0,0 +1,86 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, TotpCodeRequestDTO},
+    tokens::hash_token,
+    totp::{check_user_code, generate_recovery_codes},
+};
+use crate::domain::user_types::OneTimeCode;
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn confirm_totp(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Json(payload): Json<TotpCodeRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let code = OneTimeCode::try_from(payload.code.as_str())?;
+
+    let pending: Option<i64> = sqlx::query_scalar(
+        r#"
+            SELECT 1 FROM users
+            WHERE id = ?1 AND totp_secret IS NOT NULL AND totp_enabled_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if pending.is_none() {
+        return Err(ApiError::NotFound("no pending two-factor enrollment"));
+    }
+    if !check_user_code(&state, user_id, &code).await? {
+        return Err(ApiError::BadRequest("Invalid code"));
+    }
+
+    let recovery_codes = generate_recovery_codes();
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query(
+        r#"
+            UPDATE users SET totp_enabled_at = datetime('now') WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+            DELETE FROM recovery_codes WHERE user_id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    for recovery_code in &recovery_codes {
+        sqlx::query(
+            r#"
+                INSERT INTO recovery_codes (user_id, code_hash)
+                VALUES (?1, ?2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(hash_token(recovery_code))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Two-factor authentication enabled, store the recovery codes safely",
+            "recovery_codes": recovery_codes,
+        })),
+    ))
+}
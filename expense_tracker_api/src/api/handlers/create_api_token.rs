@@ -0,0 +1,57 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, ApiTokenDbRow, ApiTokenResponseDTO, Claims, CreateApiTokenRequest},
+    tokens::{API_TOKEN_PREFIX, generate_token, hash_token},
+};
+use crate::domain::{NewApiToken, Scope};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn create_api_token(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateApiTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Tokens may only be minted from an interactive login, never from another token.
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let new_token: NewApiToken = payload.try_into()?;
+
+    let NewApiToken {
+        name,
+        scopes,
+        expires_in_days,
+    } = new_token;
+
+    let token = format!("{API_TOKEN_PREFIX}{}", generate_token());
+
+    let row: ApiTokenDbRow = sqlx::query_as(
+        r#"
+            INSERT INTO api_tokens (user_id, name, token_hash, scopes, expires_at)
+            VALUES (?1, ?2, ?3, ?4, datetime('now', ?5))
+            RETURNING id, name, scopes, created_at, expires_at, last_used_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(name.into_inner())
+    .bind(hash_token(&token))
+    .bind(Scope::join(&scopes))
+    .bind(expires_in_days.map(|days| format!("+{days} days")))
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "msg": "Token created, it will not be shown again",
+            "token": token,
+            "details": ApiTokenResponseDTO::from(row),
+        })),
+    ))
+}
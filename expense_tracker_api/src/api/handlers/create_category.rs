@@ -0,0 +1,62 @@
+use crate::api::{
+    AppState, categories,
+    dto::{ApiError, Claims, NewCategoryRequestDTO},
+};
+use crate::domain::NewCategory;
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn create_category(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Json(payload): Json<NewCategoryRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let NewCategory {
+        name,
+        parent_id,
+        color,
+        icon,
+    } = payload.try_into()?;
+
+    let mut tx = state.pool.begin().await?;
+    if let Some(parent_id) = parent_id {
+        categories::check_parent(&mut tx, user_id, None, parent_id).await?;
+    }
+
+    let id: Option<i64> = sqlx::query_scalar(
+        r#"
+        INSERT INTO categories (user_id, name, parent_id, color, icon)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (user_id, name) DO NOTHING
+        RETURNING id
+    "#,
+    )
+    .bind(user_id)
+    .bind(name.into_inner())
+    .bind(parent_id)
+    .bind(color.map(|color| color.into_inner()))
+    .bind(icon.map(|icon| icon.into_inner()))
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let id = id.ok_or(ApiError::Conflict(
+        "A category with that name already exists",
+    ))?;
+    let category = categories::fetch(&mut *tx, user_id, id).await?;
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "msg": "Category created",
+            "category": category,
+        })),
+    ))
+}
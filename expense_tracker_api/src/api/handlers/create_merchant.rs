@@ -0,0 +1,58 @@
+use crate::api::{
+    AppState, categories,
+    dto::{ApiError, Claims, NewMerchantRequestDTO},
+    merchants,
+};
+use crate::domain::NewMerchant;
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn create_merchant(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Json(payload): Json<NewMerchantRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let NewMerchant {
+        name,
+        category_id,
+        aliases,
+    } = payload.try_into()?;
+
+    let mut tx = state.pool.begin().await?;
+    if let Some(category_id) = category_id {
+        categories::fetch(&mut *tx, user_id, category_id).await?;
+    }
+    merchants::ensure_name_free(&mut tx, user_id, &name, None).await?;
+
+    let id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO merchants (user_id, name, category_id)
+        VALUES (?1, ?2, ?3)
+        RETURNING id
+    "#,
+    )
+    .bind(user_id)
+    .bind(name.as_str())
+    .bind(category_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    merchants::set_aliases(&mut tx, user_id, id, &name, &aliases).await?;
+    let merchant = merchants::fetch(&mut *tx, user_id, id).await?;
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "msg": "Merchant created",
+            "merchant": merchant,
+        })),
+    ))
+}
@@ -1,4 +1,5 @@
 use crate::api::dto::{ApiError, CreateUserRequestDTO, UserResponseDTO};
+use crate::api::emails::send_verification_email;
 use crate::api::state::AppState;
 use crate::domain::user::NewUser;
 use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
@@ -8,7 +9,8 @@ pub async fn create_user(
     State(state): State<AppState>,
     Json(payload): Json<CreateUserRequestDTO>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let new_user: NewUser = payload.try_into()?;
+    let new_user: NewUser =
+        payload.into_new_user(&state.password_policy, state.password_hashing.argon2())?;
 
     let NewUser {
         username,
@@ -20,7 +22,8 @@ pub async fn create_user(
         r#"
         INSERT INTO users (username, email, password_hash)
         VALUES (?1, ?2, ?3)
-        RETURNING id, username, email, created_at
+        RETURNING id, username, email, email_verified_at, timezone, base_currency,
+            max_expense_amount, confirm_above, created_at
     "#,
     )
     .bind(username.into_inner())
@@ -29,10 +32,12 @@ pub async fn create_user(
     .fetch_one(&state.pool)
     .await?;
 
+    send_verification_email(&state, user.id, &user.username, &user.email).await?;
+
     Ok((
         StatusCode::CREATED,
         Json(json!({
-            "msg": "Account created successfully",
+            "msg": "Account created successfully, check your email to verify it",
             "user": user,
         })),
     ))
@@ -0,0 +1,42 @@
+use crate::api::{
+    AppState, categories,
+    dto::{ApiError, Claims, DeleteCategoryQuery},
+};
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// A category still holding expenses can only go when `reassign_to` names
+/// where those expenses move; one with subcategories cannot go at all.
+pub async fn delete_category(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<DeleteCategoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let moved = match params.reassign_to {
+        Some(target) => categories::merge(&mut tx, user_id, id, target).await?.1,
+        None => {
+            categories::delete_empty(&mut tx, user_id, id).await?;
+            0
+        }
+    };
+
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Category deleted",
+            "moved_expenses": moved,
+        })),
+    ))
+}
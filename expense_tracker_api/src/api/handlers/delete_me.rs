@@ -0,0 +1,62 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, DeleteAccountRequestDTO},
+    passwords::StoredPassword,
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+};
+
+/// Closes the account for good. Expenses are deleted explicitly because their
+/// foreign key does not cascade; sessions, refresh and API tokens, recovery
+/// codes and linked identities go with the user row.
+pub async fn delete_me(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Json(payload): Json<DeleteAccountRequestDTO>,
+) -> Result<StatusCode, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let stored: StoredPassword = sqlx::query_as(
+        r#"
+            SELECT id, password_hash, password_legacy FROM users WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::NotFound("user not found"))?;
+
+    state
+        .password_hashing
+        .verify_and_upgrade(&state.pool, &stored, &payload.password)
+        .await?
+        .ensure_verified()?;
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query(
+        r#"
+            DELETE FROM expenses WHERE user_id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+            DELETE FROM users WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,38 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims},
+    merchants,
+};
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Expenses keep the merchant's name; only its aliases and default category go.
+pub async fn delete_merchant(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let mut tx = state.pool.begin().await?;
+    merchants::fetch(&mut *tx, user_id, id).await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM merchants WHERE id = ?1 AND user_id = ?2
+    "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, Json(json!({"msg": "Merchant deleted"}))))
+}
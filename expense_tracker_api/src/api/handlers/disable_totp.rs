@@ -0,0 +1,51 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, TotpCodeRequestDTO},
+    totp::check_user_code,
+};
+use crate::domain::user_types::OneTimeCode;
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+};
+
+pub async fn disable_totp(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Json(payload): Json<TotpCodeRequestDTO>,
+) -> Result<StatusCode, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let code = OneTimeCode::try_from(payload.code.as_str())?;
+    if !check_user_code(&state, user_id, &code).await? {
+        return Err(ApiError::BadRequest("Invalid code"));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query(
+        r#"
+            UPDATE users
+            SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL
+            WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+            DELETE FROM recovery_codes WHERE user_id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
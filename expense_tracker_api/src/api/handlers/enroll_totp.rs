@@ -0,0 +1,50 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims},
+    totp::{encode_secret, generate_secret, otpauth_uri},
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn enroll_totp(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let secret = generate_secret();
+    let sealed = state.totp_cipher.encrypt(&secret)?;
+
+    // Replaces any pending enrollment; an already enabled factor must be disabled first.
+    let username: Option<String> = sqlx::query_scalar(
+        r#"
+            UPDATE users
+            SET totp_secret = ?2, totp_last_step = NULL
+            WHERE id = ?1 AND totp_enabled_at IS NULL
+            RETURNING username
+        "#,
+    )
+    .bind(user_id)
+    .bind(sealed)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let username = username.ok_or(ApiError::Conflict(
+        "two-factor authentication already enabled",
+    ))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Scan the URI with an authenticator app, then confirm with a code",
+            "secret": encode_secret(&secret),
+            "otpauth_uri": otpauth_uri(&username, &secret),
+        })),
+    ))
+}
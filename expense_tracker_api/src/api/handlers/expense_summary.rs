@@ -0,0 +1,86 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, CategoryTotalDTO, Claims, ExpenseSummaryQuery, MoneyDTO},
+    expenses,
+};
+use crate::domain::DateRange;
+use axum::{
+    Json,
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Spending per category in the user's base currency, each category's `total`
+/// and `expense_count` rolling up its subcategories while `own_total` and
+/// `own_count` do not.
+pub async fn expense_summary(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Query(params): Query<ExpenseSummaryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let dates = DateRange::parse(params.from.as_deref(), params.to.as_deref())?;
+    let (start, end) = dates.bounds();
+    let base_currency = expenses::user_settings(&state.pool, user_id)
+        .await?
+        .base_currency;
+
+    // `tree` pairs every category with itself and each of its descendants.
+    let categories: Vec<CategoryTotalDTO> = sqlx::query_as(
+        r#"
+        WITH RECURSIVE tree(root_id, id) AS (
+            SELECT id, id FROM categories WHERE user_id = ?1
+            UNION
+            SELECT tree.root_id, c.id
+            FROM categories c
+            JOIN tree ON c.parent_id = tree.id
+        ),
+        own AS (
+            SELECT c.id, COUNT(e.id) AS expense_count, COALESCE(SUM(e.base_amount), 0) AS total
+            FROM categories c
+            LEFT JOIN expenses e
+                ON e.user_id = c.user_id
+                AND e.category = c.name
+                AND (?2 IS NULL OR e.occurred_on >= ?2)
+                AND (?3 IS NULL OR e.occurred_on <= ?3)
+            WHERE c.user_id = ?1
+            GROUP BY c.id
+        )
+        SELECT c.id, c.name, c.parent_id,
+            MAX(CASE WHEN own.id = c.id THEN own.total END) AS own_total,
+            MAX(CASE WHEN own.id = c.id THEN own.expense_count END) AS own_count,
+            SUM(own.total) AS total,
+            SUM(own.expense_count) AS expense_count
+        FROM categories c
+        JOIN tree ON tree.root_id = c.id
+        JOIN own ON own.id = tree.id
+        GROUP BY c.id
+        ORDER BY total DESC, c.name
+    "#,
+    )
+    .bind(user_id)
+    .bind(&start)
+    .bind(&end)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let total: i64 = categories
+        .iter()
+        .filter(|category| category.parent_id.is_none())
+        .map(|category| category.total)
+        .sum();
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "from": dates.from,
+            "to": dates.to,
+            "currency": base_currency.code(),
+            "total": MoneyDTO::new(total, base_currency),
+            "categories": categories,
+        })),
+    ))
+}
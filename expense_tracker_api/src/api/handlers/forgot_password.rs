@@ -0,0 +1,95 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, ForgotPasswordRequestDTO},
+    tokens::{generate_token, hash_token},
+};
+use crate::domain::user_types::Email;
+use crate::mailer::{OutgoingEmail, deliver};
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde_json::json;
+
+const RESET_TOKEN_TTL_MINUTES: i64 = 30;
+
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    Json(payload): Json<ForgotPasswordRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let email = Email::try_from(payload.email)?.into_inner();
+
+    let accounts: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+            SELECT id, username FROM users WHERE email = ?1
+        "#,
+    )
+    .bind(&email)
+    .fetch_all(&state.pool)
+    .await?;
+
+    // Tokens are issued and mailed off the request path, so a known address
+    // is answered as fast as an unknown one.
+    for (user_id, username) in accounts {
+        let state = state.clone();
+        let email = email.clone();
+        tokio::spawn(async move {
+            if let Err(err) = send_reset_token(&state, user_id, &username, &email).await {
+                tracing::error!(user_id, error = %err, "failed to issue password reset token");
+            }
+        });
+    }
+
+    // Same answer whether or not the address is known.
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "msg": "If an account uses that email, a reset token has been sent",
+        })),
+    ))
+}
+
+/// Replaces the user's pending reset token with a new one and emails it.
+async fn send_reset_token(
+    state: &AppState,
+    user_id: i64,
+    username: &str,
+    email: &str,
+) -> Result<(), ApiError> {
+    let token = generate_token();
+    let mut tx = state.pool.begin().await?;
+
+    // Only the most recently requested token stays usable.
+    sqlx::query(
+        r#"
+        DELETE FROM password_reset_tokens
+        WHERE user_id = ?1 AND used_at IS NULL
+    "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO password_reset_tokens (user_id, token_hash, expires_at)
+        VALUES (?1, ?2, datetime('now', ?3))
+    "#,
+    )
+    .bind(user_id)
+    .bind(hash_token(&token))
+    .bind(format!("+{RESET_TOKEN_TTL_MINUTES} minutes"))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let mail = OutgoingEmail {
+        to: email.to_string(),
+        subject: "Reset your expense tracker password".to_string(),
+        body: format!(
+            "Hi {username},\n\nUse this token with POST /password/reset to choose a new password:\n\n{token}\n\nIt expires in {RESET_TOKEN_TTL_MINUTES} minutes. If you did not ask for this, ignore this email."
+        ),
+    };
+    if let Err(err) = deliver(state.mailer.clone(), mail).await {
+        tracing::error!(user_id, error = %err, "failed to send password reset email");
+    }
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, ExpenseRow},
+    expenses,
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH, LAST_MODIFIED},
+    },
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+pub async fn get_expense(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let expense = expenses::fetch(&state.pool, user_id, id).await?;
+    let last_modified = expense
+        .updated_at
+        .as_deref()
+        .and_then(parse_timestamp)
+        .or_else(|| parse_timestamp(&expense.created_at));
+
+    let body = serde_json::to_vec(&json!({"expense": ExpenseRow::try_from(expense)?}))
+        .map_err(|_| ApiError::Internal)?;
+    let etag = format!("\"{}\"", &hex::encode(Sha256::digest(&body))[..32]);
+
+    let mut response = if etag_matches(&headers, &etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        (
+            StatusCode::OK,
+            [(CONTENT_TYPE, HeaderValue::from_static("application/json"))],
+            body,
+        )
+            .into_response()
+    };
+
+    let response_headers = response.headers_mut();
+    response_headers.insert(ETAG, HeaderValue::from_str(&etag)?);
+    // Per-user data: clients may keep it but must revalidate before reuse.
+    response_headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+    if let Some(last_modified) = last_modified {
+        let http_date = last_modified
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        response_headers.insert(LAST_MODIFIED, HeaderValue::from_str(&http_date)?);
+    }
+
+    Ok(response)
+}
+
+/// Weak comparison as `If-None-Match` requires, including `*`.
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// Expense timestamps are RFC 3339; rows from before that used SQLite's `datetime('now')`.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .map(|timestamp| timestamp.and_utc())
+        })
+        .ok()
+}
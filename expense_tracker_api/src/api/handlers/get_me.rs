@@ -0,0 +1,33 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, UserResponseDTO},
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn get_me(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let user: UserResponseDTO = sqlx::query_as(
+        r#"
+            SELECT id, username, email, email_verified_at, timezone, base_currency,
+                max_expense_amount, confirm_above, created_at
+            FROM users
+            WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::NotFound("user not found"))?;
+
+    Ok((StatusCode::OK, Json(json!({"user": user}))))
+}
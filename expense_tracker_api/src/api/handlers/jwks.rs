@@ -0,0 +1,15 @@
+use crate::api::AppState;
+use axum::{
+    Json,
+    extract::State,
+    http::{StatusCode, header::CACHE_CONTROL},
+    response::IntoResponse,
+};
+
+pub async fn jwks(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(CACHE_CONTROL, "public, max-age=300")],
+        Json(state.keyring.jwks()),
+    )
+}
@@ -0,0 +1,36 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, ApiTokenDbRow, ApiTokenResponseDTO, Claims},
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn list_api_tokens(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let rows: Vec<ApiTokenDbRow> = sqlx::query_as(
+        r#"
+            SELECT id, name, scopes, created_at, expires_at, last_used_at
+            FROM api_tokens
+            WHERE user_id = ?1 AND revoked_at IS NULL
+            ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let tokens: Vec<ApiTokenResponseDTO> =
+        rows.into_iter().map(ApiTokenResponseDTO::from).collect();
+
+    Ok((StatusCode::OK, Json(json!({"Tokens": tokens}))))
+}
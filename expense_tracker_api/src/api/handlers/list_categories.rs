@@ -0,0 +1,22 @@
+use crate::api::{
+    AppState, categories,
+    dto::{ApiError, Claims},
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn list_categories(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let categories = categories::list(&state.pool, user_id, None).await?;
+
+    Ok((StatusCode::OK, Json(json!({"categories": categories}))))
+}
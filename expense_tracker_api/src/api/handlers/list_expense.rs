@@ -1,8 +1,10 @@
 use crate::api::{
     AppState,
-    dto::{ApiError, Claims, ExpenseDbRow, ExpenseRow, QueryExpense},
+    dto::{ApiError, Claims, ExpenseRow, ListedExpenseDbRow, ListedExpenseRow, QueryExpense},
+    expenses,
+    pagination::{Position, SortKey, decode_cursor, encode_cursor, page_size},
 };
-use crate::domain::Expense;
+use crate::domain::{Expense, ExpenseQuery, SortField, SortOrder, TagMatch};
 use axum::{
     Json,
     extract::{Extension, Query, State},
@@ -10,7 +12,12 @@ use axum::{
     response::IntoResponse,
 };
 use serde_json::json;
+use sqlx::{QueryBuilder, Sqlite};
 
+/// Filtered and sorted (most recently occurred first by default, best match first when
+/// searching), paged by keyset on `(sort column, id)` so rows sharing a sort
+/// value are neither skipped nor repeated between pages. Relevance order pages
+/// by offset instead, as scores are not stable enough to key on.
 pub async fn list_expense(
     Extension(claims): Extension<Claims>,
     State(state): State<AppState>,
@@ -21,61 +28,260 @@ pub async fn list_expense(
         .parse::<i64>()
         .map_err(|_| ApiError::Unauthorized)?;
 
-    //let like = param.search.as_deref().and_then(|s| {
-    //let s = s.trim();
-    //(!s.is_empty()).then(|| format!("%{}%", s))
-    // });
-    let search: Option<&str> = param
-        .search
+    let settings = expenses::user_settings(&state.pool, user_id).await?;
+    let query = param.to_query(settings.base_currency)?;
+    let fingerprint = query.fingerprint();
+
+    let limit = page_size(param.limit);
+    let after = param
+        .cursor
         .as_deref()
-        .map(|s| s.trim())
-        .filter(|f| !f.is_empty());
+        .map(|cursor| decode_cursor(&state.keyring, user_id, &fingerprint, cursor))
+        .transpose()?;
 
-    let rows: Vec<ExpenseDbRow> = match search {
-        Some(text) => {
-            let pattern: String = format!("%{}%", text);
-            sqlx::query_as::<_, ExpenseDbRow>(
+    let mut builder = QueryBuilder::<Sqlite>::new("");
+    match &query.search {
+        // bm25 is lower for better matches; negated so higher is better like
+        // every other descending sort.
+        Some(search) => {
+            builder.push(
                 r#"
-                SELECT id, expense_desc, amount, category, created_at
-                FROM expenses
-                WHERE user_id = ?1 
-                    AND (
-                        expense_desc LIKE ?2 COLLATE NOCASE
-                        OR category LIKE ?2 COLLATE NOCASE
-                        OR CAST(amount AS TEXT) LIKE ?2
-                    )
-                ORDER BY created_at DESC
-                LIMIT 10;
-            "#,
-            )
-            .bind(user_id)
-            .bind(&pattern)
-            .fetch_all(&state.pool)
-            .await?
+                WITH hits AS (
+                    SELECT expenses.*,
+                        -bm25(expenses_fts) AS score,
+                        snippet(expenses_fts, -1, '<mark>', '</mark>', '…', 10) AS snippet
+                    FROM expenses_fts
+                    JOIN expenses ON expenses.id = expenses_fts.rowid
+                    WHERE expenses_fts MATCH "#,
+            );
+            builder.push_bind(search.clone());
+            builder.push(" AND expenses.user_id = ");
+            builder.push_bind(user_id);
+            builder.push(
+                r#"
+                )
+                SELECT id, expense_desc, merchant, notes, amount, currency, base_amount, base_currency, exchange_rate,
+                    category, occurred_on, occurred_time, timezone, created_at,
+                    updated_at, score, snippet,
+                    (
+                        SELECT group_concat(t.name)
+                        FROM expense_tags et
+                        JOIN tags t ON t.id = et.tag_id
+                        WHERE et.expense_id = hits.id
+                    ) AS tags
+                FROM hits
+                WHERE 1 = 1"#,
+            );
         }
         None => {
-            sqlx::query_as::<_, ExpenseDbRow>(
+            builder.push(
                 r#"
-                SELECT id, expense_desc, amount, category, created_at
+                SELECT id, expense_desc, merchant, notes, amount, currency, base_amount, base_currency, exchange_rate,
+                    category, occurred_on, occurred_time, timezone, created_at,
+                    updated_at, NULL AS score, NULL AS snippet,
+                    (
+                        SELECT group_concat(t.name)
+                        FROM expense_tags et
+                        JOIN tags t ON t.id = et.tag_id
+                        WHERE et.expense_id = expenses.id
+                    ) AS tags
                 FROM expenses
-                WHERE user_id = ?1
-                ORDER BY created_at DESC
-                LIMIT 20
-            "#,
-            )
-            .bind(user_id)
-            .fetch_all(&state.pool)
-            .await?
+                WHERE user_id = "#,
+            );
+            builder.push_bind(user_id);
         }
+    }
+    push_filters(&mut builder, user_id, &query);
+
+    let column = query.sort.column();
+    let (comparison, direction) = match query.order {
+        SortOrder::Asc => (">", "ASC"),
+        SortOrder::Desc => ("<", "DESC"),
     };
 
-    let response_rows: Vec<ExpenseRow> = rows
+    let relevance = query.sort == SortField::Relevance;
+    let mut offset = 0;
+    match after {
+        None => {}
+        Some(Position::Keyset { key, id }) if !relevance => {
+            builder.push(format!(" AND ({column} {comparison} "));
+            push_key(&mut builder, &key);
+            builder.push(format!(" OR ({column} = "));
+            push_key(&mut builder, &key);
+            builder.push(format!(" AND id {comparison} "));
+            builder.push_bind(id);
+            builder.push("))");
+        }
+        Some(Position::Offset { offset: skip }) if relevance && skip >= 0 => offset = skip,
+        Some(_) => return Err(ApiError::BadRequest("Invalid cursor")),
+    }
+
+    // One row past the page tells whether another page exists.
+    builder.push(format!(
+        " ORDER BY {column} {direction}, id {direction} LIMIT "
+    ));
+    builder.push_bind(limit + 1);
+    if offset > 0 {
+        builder.push(" OFFSET ");
+        builder.push_bind(offset);
+    }
+
+    let mut rows: Vec<ListedExpenseDbRow> = builder
+        .build_query_as::<ListedExpenseDbRow>()
+        .fetch_all(&state.pool)
+        .await
+        .map_err(search_error)?;
+
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+
+    let expenses: Vec<(Expense, Option<String>)> = rows
         .into_iter()
-        .map(Expense::try_from)
-        .collect::<Result<Vec<_>, ApiError>>()?
+        .map(|row| Ok((Expense::try_from(row.expense)?, row.snippet)))
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    let next_cursor = match expenses.last() {
+        Some((last, _)) if has_more => Some(encode_cursor(
+            &state.keyring,
+            user_id,
+            &fingerprint,
+            next_position(last, query.sort, offset + expenses.len() as i64),
+        )?),
+        _ => None,
+    };
+
+    let response_rows: Vec<ListedExpenseRow> = expenses
         .into_iter()
-        .map(ExpenseRow::try_from)
+        .map(|(expense, snippet)| {
+            Ok(ListedExpenseRow {
+                expense: ExpenseRow::try_from(expense)?,
+                snippet,
+            })
+        })
         .collect::<Result<Vec<_>, ApiError>>()?;
 
-    Ok((StatusCode::OK, Json(json!({"Expenses": response_rows}))))
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "Expenses": response_rows,
+            "next_cursor": next_cursor,
+            "has_more": has_more,
+        })),
+    ))
+}
+
+/// How SQLite words the errors for a malformed FTS5 query.
+const SEARCH_SYNTAX_ERRORS: [&str; 4] = [
+    "fts5:",
+    "no such column",
+    "unterminated string",
+    "unknown special query",
+];
+
+/// The search text is FTS5 query syntax, so the database is what rejects a
+/// malformed one; that is the client's mistake, not ours.
+fn search_error(err: sqlx::Error) -> ApiError {
+    if let sqlx::Error::Database(db_err) = &err {
+        let message = db_err.message();
+        if SEARCH_SYNTAX_ERRORS
+            .iter()
+            .any(|prefix| message.starts_with(prefix))
+        {
+            return ApiError::BadRequest("Invalid search query");
+        }
+    }
+    err.into()
+}
+
+fn push_filters(builder: &mut QueryBuilder<'_, Sqlite>, user_id: i64, query: &ExpenseQuery) {
+    let (start, end) = query.dates.bounds();
+    if let Some(start) = start {
+        builder.push(" AND occurred_on >= ");
+        builder.push_bind(start);
+    }
+    if let Some(end) = end {
+        builder.push(" AND occurred_on <= ");
+        builder.push_bind(end);
+    }
+
+    // A category matches its own expenses and those of all its subcategories.
+    if !query.categories.is_empty() {
+        builder.push(
+            r#"
+            AND category IN (
+                WITH RECURSIVE tree(id, name) AS (
+                    SELECT id, name FROM categories
+                    WHERE user_id = "#,
+        );
+        builder.push_bind(user_id);
+        builder.push(" AND name IN (");
+        let mut separated = builder.separated(", ");
+        for category in &query.categories {
+            separated.push_bind(category.as_str().to_string());
+        }
+        separated.push_unseparated(")");
+        builder.push(
+            r#"
+                    UNION
+                    SELECT c.id, c.name FROM categories c JOIN tree ON c.parent_id = tree.id
+                )
+                SELECT name FROM tree
+            )"#,
+        );
+    }
+
+    if let Some(currency) = query.currency {
+        builder.push(" AND currency = ");
+        builder.push_bind(currency.code());
+    }
+    if let Some(min) = query.min_amount {
+        builder.push(" AND base_amount >= ");
+        builder.push_bind(min.minor_units());
+    }
+    if let Some(max) = query.max_amount {
+        builder.push(" AND base_amount <= ");
+        builder.push_bind(max.minor_units());
+    }
+
+    if !query.tags.is_empty() {
+        builder.push(
+            r#"
+            AND id IN (
+                SELECT et.expense_id
+                FROM expense_tags et
+                JOIN tags t ON t.id = et.tag_id
+                WHERE t.user_id = "#,
+        );
+        builder.push_bind(user_id);
+        builder.push(" AND t.name IN (");
+        let mut separated = builder.separated(", ");
+        for tag in &query.tags {
+            separated.push_bind(tag.as_str().to_string());
+        }
+        separated.push_unseparated(")");
+        builder.push(" GROUP BY et.expense_id");
+        if query.tag_match == TagMatch::All {
+            builder.push(" HAVING COUNT(*) = ");
+            builder.push_bind(query.tags.len() as i64);
+        }
+        builder.push(")");
+    }
+}
+
+fn push_key(builder: &mut QueryBuilder<'_, Sqlite>, key: &SortKey) {
+    match key {
+        SortKey::Integer(value) => builder.push_bind(*value),
+        SortKey::Text(value) => builder.push_bind(value.clone()),
+    };
+}
+
+/// Cursor position after `last`, having returned `returned` rows in total.
+fn next_position(last: &Expense, sort: SortField, returned: i64) -> Position {
+    let key = match sort {
+        SortField::Date => SortKey::Text(last.occurred_on.to_string()),
+        SortField::Amount => SortKey::Integer(last.base_amount.minor_units()),
+        SortField::Category => SortKey::Text(last.category.as_str().to_string()),
+        SortField::Relevance => return Position::Offset { offset: returned },
+    };
+    Position::Keyset { key, id: last.id }
 }
@@ -0,0 +1,32 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, MerchantQuery},
+    merchants,
+};
+use axum::{
+    Json,
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// With `prefix`, autocompletes merchant names and aliases as they are typed.
+pub async fn list_merchants(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Query(params): Query<MerchantQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let prefix = params
+        .prefix
+        .as_deref()
+        .map(str::trim)
+        .filter(|prefix| !prefix.is_empty());
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    let merchants = merchants::list(&state.pool, user_id, None, prefix, limit).await?;
+
+    Ok((StatusCode::OK, Json(json!({"merchants": merchants}))))
+}
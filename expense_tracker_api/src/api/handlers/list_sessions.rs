@@ -0,0 +1,48 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, SessionResponseDTO},
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Sessions that can still be used or refreshed, most recently active first.
+pub async fn list_sessions(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sid = claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let sessions: Vec<SessionResponseDTO> = sqlx::query_as(
+        r#"
+            SELECT
+                s.id,
+                s.user_agent,
+                s.ip,
+                s.created_at,
+                s.last_seen_at,
+                s.id = ?2 AS current
+            FROM sessions s
+            WHERE s.user_id = ?1
+                AND s.revoked_at IS NULL
+                AND EXISTS (
+                    SELECT 1 FROM refresh_tokens rt
+                    WHERE rt.session_id = s.id
+                        AND rt.used_at IS NULL
+                        AND rt.expires_at > datetime('now')
+                )
+            ORDER BY s.last_seen_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(sid)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok((StatusCode::OK, Json(json!({"sessions": sessions}))))
+}
@@ -0,0 +1,55 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, ExpenseSummaryQuery, TagTotalDTO},
+    expenses,
+};
+use crate::domain::DateRange;
+use axum::{
+    Json,
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Tag cloud: every tag in use with how many expenses carry it and their total.
+pub async fn list_tags(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Query(params): Query<ExpenseSummaryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let dates = DateRange::parse(params.from.as_deref(), params.to.as_deref())?;
+    let (start, end) = dates.bounds();
+    let base_currency = expenses::user_settings(&state.pool, user_id)
+        .await?
+        .base_currency;
+
+    let tags: Vec<TagTotalDTO> = sqlx::query_as(
+        r#"
+        SELECT t.name, COUNT(e.id) AS expense_count, SUM(e.base_amount) AS total
+        FROM tags t
+        JOIN expense_tags et ON et.tag_id = t.id
+        JOIN expenses e ON e.id = et.expense_id
+        WHERE t.user_id = ?1
+            AND (?2 IS NULL OR e.occurred_on >= ?2)
+            AND (?3 IS NULL OR e.occurred_on <= ?3)
+        GROUP BY t.id
+        ORDER BY expense_count DESC, t.name
+    "#,
+    )
+    .bind(user_id)
+    .bind(&start)
+    .bind(&end)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "currency": base_currency.code(),
+            "tags": tags,
+        })),
+    ))
+}
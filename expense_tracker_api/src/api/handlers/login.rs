@@ -1,68 +1,111 @@
 use crate::api::{
-    dto::{ApiError, Claims, LoginRequestDTO},
+    auth::ClientInfo,
+    dto::{ApiError, LoginRequestDTO},
+    passwords::{PASSWORD_RESET_REQUIRED, PasswordCheck, StoredPassword},
     state::AppState,
+    throttle::{self, Throttle},
+    tokens::{
+        ACCESS_TOKEN_TTL_SECS, CHALLENGE_TOKEN_TTL_SECS, TokenPair, issue_challenge_token,
+        start_session,
+    },
+};
+use axum::{
+    Json,
+    extract::{ConnectInfo, State},
+    http::StatusCode,
+    response::IntoResponse,
 };
-use argon2::{Argon2, PasswordHash, PasswordVerifier};
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
-use chrono::{Duration, Utc};
-use jsonwebtoken::{EncodingKey, Header, encode};
 use serde_json::json;
 use sqlx::prelude::FromRow;
+use std::net::SocketAddr;
 
 #[derive(FromRow)]
 struct AuthUser {
-    id: i64,
-    password_hash: String,
+    #[sqlx(flatten)]
+    password: StoredPassword,
+    totp_enabled: bool,
 }
 
 pub async fn login(
     State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    client: ClientInfo,
     Json(payload): Json<LoginRequestDTO>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let ip = addr.ip().to_string();
+    let username_key = payload.username.trim().to_ascii_lowercase();
+
+    throttle::ensure_allowed(&state.pool, Throttle::Ip, &ip).await?;
+    throttle::ensure_allowed(&state.pool, Throttle::Username, &username_key).await?;
+
     let row = sqlx::query_as::<_, AuthUser>(
         r#"
-        SELECT * FROM users WHERE username = ?1
+        SELECT
+            id,
+            password_hash,
+            password_legacy,
+            totp_enabled_at IS NOT NULL AS totp_enabled
+        FROM users
+        WHERE username = ?1
     "#,
     )
     .bind(&payload.username)
     .fetch_optional(&state.pool)
-    .await?
-    .ok_or(ApiError::Unauthorized)?;
+    .await?;
 
-    let parsed = match PasswordHash::new(&row.password_hash) {
-        Ok(hash) => hash,
-        Err(_) => {
-            return Err(ApiError::Internal);
+    let check = match &row {
+        Some(user) => {
+            state
+                .password_hashing
+                .verify_and_upgrade(&state.pool, &user.password, &payload.password)
+                .await?
+        }
+        None => {
+            state.password_hashing.verify_dummy(&payload.password);
+            PasswordCheck::Failed
         }
     };
-    if Argon2::default()
-        .verify_password(payload.password.as_bytes(), &parsed)
-        .is_err()
-    {
-        return Err(ApiError::Unauthorized);
-    }
-    let issued_at = Utc::now();
-    let expiration = issued_at + Duration::hours(1);
 
-    let claims: Claims = Claims {
-        sub: row.id.to_string(),
-        exp: expiration.timestamp(),
-        iat: issued_at.timestamp(),
+    let row = match row {
+        Some(row) if check == PasswordCheck::Verified => row,
+        _ => {
+            throttle::record_failure(&state.pool, Throttle::Ip, &ip).await?;
+            throttle::record_failure(&state.pool, Throttle::Username, &username_key).await?;
+            return Err(match check {
+                PasswordCheck::ResetRequired => ApiError::Forbidden(PASSWORD_RESET_REQUIRED),
+                _ => ApiError::Unauthorized,
+            });
+        }
     };
+    throttle::record_success(&state.pool, Throttle::Username, &username_key).await?;
+    let user_id = row.password.id;
+
+    if row.totp_enabled {
+        let challenge_token = issue_challenge_token(&state.keyring, user_id)?;
+        return Ok((
+            StatusCode::OK,
+            Json(json!({
+                "msg": "Two-factor code required",
+                "two_factor_required": true,
+                "challenge_token": challenge_token,
+                "expires_in": CHALLENGE_TOKEN_TTL_SECS,
+            })),
+        ));
+    }
 
-    let key = &state.jwt_secret;
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(key.as_bytes()),
-    )?;
+    let TokenPair {
+        access_token,
+        refresh_token,
+    } = start_session(&state.pool, &state.keyring, user_id, &client).await?;
 
     Ok((
         StatusCode::OK,
         Json(json!({
             "msg": "Login successful",
-            "token": token,
-            "type": "Bearer"
+            "token": access_token,
+            "type": "Bearer",
+            "expires_in": ACCESS_TOKEN_TTL_SECS,
+            "refresh_token": refresh_token,
         })),
     ))
 }
@@ -0,0 +1,71 @@
+use crate::api::{
+    auth::ClientInfo,
+    dto::{ApiError, TwoFactorLoginRequestDTO},
+    state::AppState,
+    throttle::{self, Throttle},
+    tokens::{ACCESS_TOKEN_TTL_SECS, TokenPair, hash_token, start_session, verify_challenge_token},
+    totp::check_user_code,
+};
+use crate::domain::user_types::OneTimeCode;
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde_json::json;
+
+pub async fn login_two_factor(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<TwoFactorLoginRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id = verify_challenge_token(&state.keyring, &payload.challenge_token)?;
+    let throttle_key = user_id.to_string();
+    throttle::ensure_allowed(&state.pool, Throttle::Totp, &throttle_key).await?;
+
+    let verified = match (payload.code, payload.recovery_code) {
+        (Some(code), None) => {
+            let code = OneTimeCode::try_from(code.as_str())?;
+            check_user_code(&state, user_id, &code).await?
+        }
+        (None, Some(recovery_code)) => {
+            let used = sqlx::query(
+                r#"
+                UPDATE recovery_codes
+                SET used_at = datetime('now')
+                WHERE user_id = ?1 AND code_hash = ?2 AND used_at IS NULL
+            "#,
+            )
+            .bind(user_id)
+            .bind(hash_token(
+                recovery_code.trim().to_ascii_lowercase().as_str(),
+            ))
+            .execute(&state.pool)
+            .await?;
+            used.rows_affected() == 1
+        }
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Provide either a code or a recovery code",
+            ));
+        }
+    };
+
+    if !verified {
+        throttle::record_failure(&state.pool, Throttle::Totp, &throttle_key).await?;
+        return Err(ApiError::Unauthorized);
+    }
+    throttle::record_success(&state.pool, Throttle::Totp, &throttle_key).await?;
+
+    let TokenPair {
+        access_token,
+        refresh_token,
+    } = start_session(&state.pool, &state.keyring, user_id, &client).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Login successful",
+            "token": access_token,
+            "type": "Bearer",
+            "expires_in": ACCESS_TOKEN_TTL_SECS,
+            "refresh_token": refresh_token,
+        })),
+    ))
+}
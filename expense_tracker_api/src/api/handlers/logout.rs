@@ -0,0 +1,29 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims},
+};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+};
+
+pub async fn logout(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    sqlx::query(
+        r#"
+            UPDATE sessions
+            SET revoked_at = datetime('now')
+            WHERE id = ?1 AND user_id = ?2 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(claims.session_id()?)
+    .bind(user_id)
+    .execute(&state.pool)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,34 @@
+use crate::api::{
+    AppState, categories,
+    dto::{ApiError, Claims, MergeCategoryRequestDTO},
+};
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Folds category `id` into another one, which keeps its expenses.
+pub async fn merge_category(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<MergeCategoryRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let mut tx = state.pool.begin().await?;
+    let (category, moved) = categories::merge(&mut tx, user_id, id, payload.into).await?;
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Categories merged",
+            "moved_expenses": moved,
+            "category": category,
+        })),
+    ))
+}
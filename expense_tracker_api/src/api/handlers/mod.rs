@@ -1,13 +1,103 @@
+pub mod admin_audit_log;
+pub mod admin_disable_user;
+pub mod admin_enable_user;
+pub mod admin_get_settings;
+pub mod admin_import_exchange_rates;
+pub mod admin_list_user_expenses;
+pub mod admin_list_users;
+pub mod admin_logout_user;
+pub mod admin_set_role;
+pub mod admin_update_settings;
+pub mod change_password;
+pub mod confirm_email_change;
+pub mod confirm_totp;
+pub mod create_api_token;
+pub mod create_category;
+pub mod create_merchant;
 pub mod create_user;
+pub mod delete_category;
 pub mod delete_expense;
+pub mod delete_me;
+pub mod delete_merchant;
+pub mod disable_totp;
+pub mod enroll_totp;
+pub mod expense_summary;
+pub mod forgot_password;
+pub mod get_expense;
+pub mod get_me;
+pub mod jwks;
+pub mod list_api_tokens;
+pub mod list_categories;
 pub mod list_expense;
+pub mod list_merchants;
+pub mod list_sessions;
+pub mod list_tags;
 pub mod login;
+pub mod login_two_factor;
+pub mod logout;
+pub mod merge_category;
 pub mod new_expense;
+pub mod oidc_callback;
+pub mod oidc_login;
+pub mod refresh_token;
+pub mod resend_verification;
+pub mod reset_password;
+pub mod revoke_api_token;
+pub mod revoke_session;
+pub mod update_category;
 pub mod update_expense;
+pub mod update_me;
+pub mod update_merchant;
+pub mod verify_email;
 
+pub use admin_audit_log::admin_audit_log;
+pub use admin_disable_user::admin_disable_user;
+pub use admin_enable_user::admin_enable_user;
+pub use admin_get_settings::admin_get_settings;
+pub use admin_import_exchange_rates::admin_import_exchange_rates;
+pub use admin_list_user_expenses::admin_list_user_expenses;
+pub use admin_list_users::admin_list_users;
+pub use admin_logout_user::admin_logout_user;
+pub use admin_set_role::admin_set_role;
+pub use admin_update_settings::admin_update_settings;
+pub use change_password::change_password;
+pub use confirm_email_change::confirm_email_change;
+pub use confirm_totp::confirm_totp;
+pub use create_api_token::create_api_token;
+pub use create_category::create_category;
+pub use create_merchant::create_merchant;
 pub use create_user::create_user;
+pub use delete_category::delete_category;
 pub use delete_expense::delete_expense;
+pub use delete_me::delete_me;
+pub use delete_merchant::delete_merchant;
+pub use disable_totp::disable_totp;
+pub use enroll_totp::enroll_totp;
+pub use expense_summary::expense_summary;
+pub use forgot_password::forgot_password;
+pub use get_expense::get_expense;
+pub use get_me::get_me;
+pub use jwks::jwks;
+pub use list_api_tokens::list_api_tokens;
+pub use list_categories::list_categories;
 pub use list_expense::list_expense;
+pub use list_merchants::list_merchants;
+pub use list_sessions::list_sessions;
+pub use list_tags::list_tags;
 pub use login::login;
+pub use login_two_factor::login_two_factor;
+pub use logout::logout;
+pub use merge_category::merge_category;
 pub use new_expense::new_expense;
+pub use oidc_callback::oidc_callback;
+pub use oidc_login::oidc_login;
+pub use refresh_token::refresh_token;
+pub use resend_verification::resend_verification;
+pub use reset_password::reset_password;
+pub use revoke_api_token::revoke_api_token;
+pub use revoke_session::revoke_session;
+pub use update_category::update_category;
 pub use update_expense::update_expense;
+pub use update_me::update_me;
+pub use update_merchant::update_merchant;
+pub use verify_email::verify_email;
@@ -1,8 +1,9 @@
 use crate::api::{
-    AppState,
-    dto::{ApiError, Claims, ExpenseResponseDTO, NewExpenseRequest},
+    AppState, categories,
+    dto::{ApiError, Claims, ExpenseRow, NewExpenseRequest},
+    exchange_rates, expenses, merchants, tags,
 };
-use crate::domain::NewExpense;
+use crate::domain::{Merchant, NewExpense, Notes};
 use axum::{self, Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde_json::json;
 
@@ -16,37 +17,83 @@ pub async fn new_expense(
         .parse::<i64>()
         .map_err(|_| ApiError::Unauthorized)?;
 
-    let new_expense: NewExpense = payload.try_into()?;
+    let mut tx = state.pool.begin().await?;
+    let settings = expenses::user_settings(&mut *tx, user_id).await?;
 
     let NewExpense {
         expense_desc,
+        merchant,
+        notes,
         amount,
         category,
+        tags,
+        occurred_on,
+        occurred_time,
+        timezone,
+        confirmed,
         created_at,
-    } = new_expense;
+    } = payload.into_new_expense(&settings)?;
 
-    let expense: ExpenseResponseDTO = sqlx::query_as(
+    let (merchant, default_category) = match merchant {
+        Some(merchant) => {
+            let (merchant, category) = merchants::resolve(&mut tx, user_id, &merchant).await?;
+            (Some(merchant), category)
+        }
+        None => (None, None),
+    };
+    let category_from_merchant = category.is_none() && default_category.is_some();
+    let category = category.or(default_category).ok_or(ApiError::BadRequest(
+        "category is required unless the merchant has a default category",
+    ))?;
+    let category = categories::resolve(&mut *tx, user_id, &category).await?;
+    let rate = exchange_rates::rate(
+        &mut *tx,
+        amount.currency(),
+        settings.base_currency,
+        occurred_on,
+    )
+    .await?;
+    let base_amount = amount.convert(rate, settings.base_currency);
+    settings.limits.check(base_amount, confirmed)?;
+
+    let id: i64 = sqlx::query_scalar(
         r#"
             INSERT INTO expenses 
-                (expense_desc, amount, category, created_at, user_id)
+                (expense_desc, amount, currency, base_amount, base_currency, exchange_rate,
+                category, occurred_on, occurred_time, timezone, created_at, updated_at, user_id,
+                merchant, notes)
             VALUES
-                (?1, ?2, ?3, ?4, ?5)
-            RETURNING id, expense_desc, amount, category, created_at
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11, ?12, ?13, ?14)
+            RETURNING id
         "#,
     )
     .bind(expense_desc.into_inner())
-    .bind(amount.as_i64())
+    .bind(amount.minor_units())
+    .bind(amount.currency().code())
+    .bind(base_amount.minor_units())
+    .bind(base_amount.currency().code())
+    .bind(rate)
     .bind(category.into_inner())
+    .bind(occurred_on.to_string())
+    .bind(occurred_time.map(|time| time.format("%H:%M:%S").to_string()))
+    .bind(timezone.name())
     .bind(created_at)
     .bind(user_id)
-    .fetch_one(&state.pool)
+    .bind(merchant.map(Merchant::into_inner))
+    .bind(notes.map(Notes::into_inner))
+    .fetch_one(&mut *tx)
     .await?;
 
+    tags::attach(&mut tx, user_id, id, &tags).await?;
+    let expense = expenses::fetch(&mut *tx, user_id, id).await?;
+    tx.commit().await?;
+
     Ok((
         StatusCode::CREATED,
         Json(json!({
         "msg": "Expense added successfully",
-        "expense": expense
+        "expense": ExpenseRow::try_from(expense)?,
+        "category_from_merchant": category_from_merchant
         })),
     ))
 }
@@ -0,0 +1,273 @@
+use crate::api::{
+    AppState,
+    auth::ClientInfo,
+    dto::{ApiError, OidcCallbackQuery},
+    emails::send_verification_email,
+    oidc::{IdTokenClaims, OIDC_STATE_COOKIE},
+    tokens::{
+        ACCESS_TOKEN_TTL_SECS, CHALLENGE_TOKEN_TTL_SECS, TokenPair, generate_token, hash_token,
+        issue_challenge_token, start_session,
+    },
+};
+use crate::domain::user_types::{Email, Password, UserName};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header::COOKIE},
+    response::IntoResponse,
+};
+use serde_json::json;
+
+const USERNAME_MAX_LEN: usize = 15;
+const USERNAME_ATTEMPTS: usize = 20;
+
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    client: ClientInfo,
+    Query(params): Query<OidcCallbackQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let oidc = state
+        .oidc
+        .as_ref()
+        .ok_or(ApiError::NotFound("Single sign-on is not configured"))?;
+
+    if params.error.is_some() {
+        return Err(ApiError::BadRequest(
+            "Sign-in was cancelled or denied at the identity provider",
+        ));
+    }
+    let (Some(code), Some(login_state)) = (params.code, params.state) else {
+        return Err(ApiError::BadRequest("Missing code or state"));
+    };
+    if state_cookie(&headers) != Some(login_state.as_str()) {
+        return Err(ApiError::BadRequest("Invalid or expired sign-in attempt"));
+    }
+
+    // Consuming the row makes every state value single use.
+    let (nonce, code_verifier): (String, String) = sqlx::query_as(
+        r#"
+        DELETE FROM oidc_logins
+        WHERE state_hash = ?1 AND expires_at > datetime('now')
+        RETURNING nonce, code_verifier
+    "#,
+    )
+    .bind(hash_token(&login_state))
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::BadRequest("Invalid or expired sign-in attempt"))?;
+
+    let claims = oidc.exchange_code(&code, &code_verifier, &nonce).await?;
+    let user_id = link_or_provision(&state, oidc.issuer(), &claims).await?;
+
+    let totp_enabled: bool = sqlx::query_scalar(
+        r#"
+        SELECT totp_enabled_at IS NOT NULL FROM users WHERE id = ?1
+    "#,
+    )
+    .bind(user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    // A second factor enrolled here is still asked for, as with a password login.
+    if totp_enabled {
+        let challenge_token = issue_challenge_token(&state.keyring, user_id)?;
+        return Ok((
+            StatusCode::OK,
+            Json(json!({
+                "msg": "Two-factor code required",
+                "two_factor_required": true,
+                "challenge_token": challenge_token,
+                "expires_in": CHALLENGE_TOKEN_TTL_SECS,
+            })),
+        ));
+    }
+
+    let TokenPair {
+        access_token,
+        refresh_token,
+    } = start_session(&state.pool, &state.keyring, user_id, &client).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Login successful",
+            "token": access_token,
+            "type": "Bearer",
+            "expires_in": ACCESS_TOKEN_TTL_SECS,
+            "refresh_token": refresh_token,
+        })),
+    ))
+}
+
+fn state_cookie(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == OIDC_STATE_COOKIE)
+        .map(|(_, value)| value)
+}
+
+/// Finds the account for the external identity. Unknown identities are linked
+/// to the one account already holding the same verified email, otherwise a new
+/// account is created for them. Addresses only assumed verified by the
+/// migration that introduced verification never match.
+async fn link_or_provision(
+    state: &AppState,
+    issuer: &str,
+    claims: &IdTokenClaims,
+) -> Result<i64, ApiError> {
+    let linked: Option<i64> = sqlx::query_scalar(
+        r#"
+        UPDATE user_identities
+        SET last_login_at = datetime('now')
+        WHERE issuer = ?1 AND subject = ?2
+        RETURNING user_id
+    "#,
+    )
+    .bind(issuer)
+    .bind(&claims.sub)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if let Some(user_id) = linked {
+        return Ok(user_id);
+    }
+
+    let email = claims
+        .email
+        .clone()
+        .and_then(|email| Email::try_from(email).ok())
+        .ok_or(ApiError::BadRequest(
+            "The identity provider did not share a valid email address",
+        ))?
+        .into_inner();
+
+    if claims.email_verified {
+        let matches: Vec<i64> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM users
+            WHERE email = ?1 AND email_verified_at IS NOT NULL AND NOT email_verified_legacy
+        "#,
+        )
+        .bind(&email)
+        .fetch_all(&state.pool)
+        .await?;
+
+        if let [user_id] = matches[..] {
+            insert_identity(&state.pool, user_id, issuer, &claims.sub).await?;
+            return Ok(user_id);
+        }
+    }
+
+    let username = free_username(state, claims, &email).await?;
+    // The account gets a random password; a local one can be set through a reset.
+    let password_hash = state
+        .password_hashing
+        .hash(Password::new(generate_token()))?;
+
+    let mut tx = state.pool.begin().await?;
+    let user_id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO users (username, email, password_hash, password_legacy, email_verified_at)
+        VALUES (?1, ?2, ?3, 0, CASE WHEN ?4 THEN datetime('now') END)
+        RETURNING id
+    "#,
+    )
+    .bind(&username)
+    .bind(&email)
+    .bind(password_hash.into_inner())
+    .bind(claims.email_verified)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    insert_identity(&mut *tx, user_id, issuer, &claims.sub).await?;
+    tx.commit().await?;
+
+    if !claims.email_verified {
+        send_verification_email(state, user_id, &username, &email).await?;
+    }
+
+    Ok(user_id)
+}
+
+async fn insert_identity<'e, E>(
+    executor: E,
+    user_id: i64,
+    issuer: &str,
+    subject: &str,
+) -> Result<(), ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO user_identities (user_id, issuer, subject, last_login_at)
+        VALUES (?1, ?2, ?3, datetime('now'))
+    "#,
+    )
+    .bind(user_id)
+    .bind(issuer)
+    .bind(subject)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Derives a valid, unused username from the provider's preferred username or
+/// the email's local part, adding a numeric suffix when it is taken.
+async fn free_username(
+    state: &AppState,
+    claims: &IdTokenClaims,
+    email: &str,
+) -> Result<String, ApiError> {
+    let source = claims
+        .preferred_username
+        .as_deref()
+        .unwrap_or_else(|| email.split('@').next().unwrap_or_default());
+
+    let mut base: String = source
+        .to_ascii_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .skip_while(|c| *c == '_')
+        .take(USERNAME_MAX_LEN)
+        .collect();
+    if base.len() < 3 {
+        base = "user".to_string();
+    }
+
+    for attempt in 0..USERNAME_ATTEMPTS {
+        let candidate = if attempt == 0 {
+            base.clone()
+        } else {
+            let suffix = format!("{}", OsRng.next_u32() % 10_000);
+            let keep = USERNAME_MAX_LEN - suffix.len();
+            format!("{}{suffix}", &base[..base.len().min(keep)])
+        };
+        let Ok(username) = UserName::try_from(candidate) else {
+            continue;
+        };
+        let username = username.into_inner();
+
+        let taken: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT 1 FROM users WHERE username = ?1
+        "#,
+        )
+        .bind(&username)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        if taken.is_none() {
+            return Ok(username);
+        }
+    }
+
+    Err(ApiError::Conflict("Could not find a free username"))
+}
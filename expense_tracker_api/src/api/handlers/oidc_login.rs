@@ -0,0 +1,60 @@
+use crate::api::{
+    AppState,
+    dto::ApiError,
+    oidc::{OIDC_LOGIN_TTL_MINUTES, OIDC_STATE_COOKIE},
+    tokens::{generate_token, hash_token},
+};
+use axum::{
+    extract::State,
+    http::{HeaderValue, header::SET_COOKIE},
+    response::{IntoResponse, Redirect},
+};
+
+pub async fn oidc_login(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let oidc = state
+        .oidc
+        .as_ref()
+        .ok_or(ApiError::NotFound("Single sign-on is not configured"))?;
+
+    let login_state = generate_token();
+    let nonce = generate_token();
+    let code_verifier = generate_token();
+
+    let authorization_url = oidc
+        .authorization_url(&login_state, &nonce, &code_verifier)
+        .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM oidc_logins WHERE expires_at <= datetime('now')
+    "#,
+    )
+    .execute(&state.pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO oidc_logins (state_hash, nonce, code_verifier, expires_at)
+        VALUES (?1, ?2, ?3, datetime('now', ?4))
+    "#,
+    )
+    .bind(hash_token(&login_state))
+    .bind(&nonce)
+    .bind(&code_verifier)
+    .bind(format!("+{OIDC_LOGIN_TTL_MINUTES} minutes"))
+    .execute(&state.pool)
+    .await?;
+
+    let secure = if state.public_url.starts_with("https://") {
+        "; Secure"
+    } else {
+        ""
+    };
+    let cookie = format!(
+        "{OIDC_STATE_COOKIE}={login_state}; Path=/oidc; Max-Age={}; HttpOnly; SameSite=Lax{secure}",
+        OIDC_LOGIN_TTL_MINUTES * 60
+    );
+    let cookie = HeaderValue::from_str(&cookie).map_err(|_| ApiError::Internal)?;
+
+    Ok(([(SET_COOKIE, cookie)], Redirect::to(&authorization_url)))
+}
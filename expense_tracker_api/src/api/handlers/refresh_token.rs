@@ -0,0 +1,112 @@
+use crate::api::{
+    dto::{ApiError, RefreshRequestDTO},
+    state::AppState,
+    tokens::{
+        ACCESS_TOKEN_TTL_SECS, active_role, hash_token, insert_refresh_token, issue_access_token,
+    },
+};
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde_json::json;
+use sqlx::prelude::FromRow;
+
+#[derive(FromRow)]
+struct RefreshRow {
+    id: i64,
+    session_id: String,
+    user_id: i64,
+    used: bool,
+    expired: bool,
+    revoked: bool,
+}
+
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut tx = state.pool.begin().await?;
+
+    let row = sqlx::query_as::<_, RefreshRow>(
+        r#"
+        SELECT
+            rt.id,
+            rt.session_id,
+            s.user_id,
+            rt.used_at IS NOT NULL AS used,
+            rt.expires_at <= datetime('now') AS expired,
+            s.revoked_at IS NOT NULL AS revoked
+        FROM refresh_tokens rt
+        JOIN sessions s ON s.id = rt.session_id
+        WHERE rt.token_hash = ?1
+    "#,
+    )
+    .bind(hash_token(&payload.refresh_token))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::Unauthorized)?;
+
+    if row.revoked || row.expired {
+        return Err(ApiError::Unauthorized);
+    }
+
+    // Marking the token used only succeeds once, so a concurrent replay of the
+    // same token is caught here as well.
+    let rotated = !row.used
+        && sqlx::query(
+            r#"
+            UPDATE refresh_tokens
+            SET used_at = datetime('now')
+            WHERE id = ?1 AND used_at IS NULL
+        "#,
+        )
+        .bind(row.id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+    if !rotated {
+        // A rotated-out token came back: assume it leaked and kill the whole family.
+        sqlx::query(
+            r#"
+            UPDATE sessions
+            SET revoked_at = datetime('now')
+            WHERE id = ?1 AND revoked_at IS NULL
+        "#,
+        )
+        .bind(&row.session_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        tracing::warn!(
+            session_id = %row.session_id,
+            "refresh token reuse detected, session revoked"
+        );
+        return Err(ApiError::Unauthorized);
+    }
+
+    let role = active_role(&mut *tx, row.user_id).await?;
+    sqlx::query(
+        r#"
+        UPDATE sessions SET last_seen_at = datetime('now') WHERE id = ?1
+    "#,
+    )
+    .bind(&row.session_id)
+    .execute(&mut *tx)
+    .await?;
+    let refresh_token = insert_refresh_token(&mut *tx, &row.session_id).await?;
+    tx.commit().await?;
+
+    let access_token = issue_access_token(&state.keyring, row.user_id, &row.session_id, role)?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Token refreshed",
+            "token": access_token,
+            "type": "Bearer",
+            "expires_in": ACCESS_TOKEN_TTL_SECS,
+            "refresh_token": refresh_token,
+        })),
+    ))
+}
@@ -0,0 +1,43 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims},
+    emails::send_verification_email,
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn resend_verification(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let (username, email, verified): (String, String, bool) = sqlx::query_as(
+        r#"
+            SELECT username, email, email_verified_at IS NOT NULL
+            FROM users
+            WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::NotFound("user not found"))?;
+
+    if verified {
+        return Err(ApiError::Conflict("email already verified"));
+    }
+
+    send_verification_email(&state, user_id, &username, &email).await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({"msg": "Verification email sent"})),
+    ))
+}
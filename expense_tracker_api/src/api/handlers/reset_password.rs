@@ -0,0 +1,66 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, ResetPasswordRequestDTO},
+    tokens::hash_token,
+};
+use axum::{Json, extract::State, http::StatusCode};
+
+pub async fn reset_password(
+    State(state): State<AppState>,
+    Json(payload): Json<ResetPasswordRequestDTO>,
+) -> Result<StatusCode, ApiError> {
+    let new_password = state.password_policy.validate(payload.new_password)?;
+    let new_hash = state.password_hashing.hash(new_password)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let user_id: i64 = sqlx::query_scalar(
+        r#"
+            UPDATE password_reset_tokens
+            SET used_at = datetime('now')
+            WHERE token_hash = ?1 AND used_at IS NULL AND expires_at > datetime('now')
+            RETURNING user_id
+        "#,
+    )
+    .bind(hash_token(payload.token.trim()))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::BadRequest("Invalid or expired token"))?;
+
+    sqlx::query(
+        r#"
+            UPDATE users SET password_hash = ?2, password_legacy = 0 WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .bind(new_hash.into_inner())
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+            UPDATE sessions
+            SET revoked_at = datetime('now')
+            WHERE user_id = ?1 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // A reset usually means the credentials leaked, so API tokens go too.
+    sqlx::query(
+        r#"
+            UPDATE api_tokens
+            SET revoked_at = datetime('now')
+            WHERE user_id = ?1 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
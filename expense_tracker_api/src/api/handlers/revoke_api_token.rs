@@ -0,0 +1,34 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims},
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+
+pub async fn revoke_api_token(
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let revoke = sqlx::query(
+        r#"
+            UPDATE api_tokens
+            SET revoked_at = datetime('now')
+            WHERE id = ?1 AND user_id = ?2 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(&state.pool)
+    .await?;
+
+    if revoke.rows_affected() == 0 {
+        return Err(ApiError::NotFound("token not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,37 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims},
+};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+
+/// Signs one session out, e.g. a lost phone. Its access tokens stop working
+/// immediately and its refresh token can no longer be used.
+pub async fn revoke_session(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let revoked = sqlx::query(
+        r#"
+            UPDATE sessions
+            SET revoked_at = datetime('now')
+            WHERE id = ?1 AND user_id = ?2 AND revoked_at IS NULL
+        "#,
+    )
+    .bind(&id)
+    .bind(user_id)
+    .execute(&state.pool)
+    .await?;
+
+    if revoked.rows_affected() == 0 {
+        return Err(ApiError::NotFound("session not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
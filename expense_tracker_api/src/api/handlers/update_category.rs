@@ -0,0 +1,107 @@
+use crate::api::{
+    AppState, categories,
+    dto::{ApiError, Claims, UpdateCategoryRequestDTO},
+};
+use crate::domain::CategoryChanges;
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Renaming also moves the category's existing expenses to the new name;
+/// `parent_id` moves the category, with its subcategories, in the tree.
+pub async fn update_category(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<UpdateCategoryRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let CategoryChanges {
+        name,
+        parent_id,
+        color,
+        icon,
+    } = payload.try_into()?;
+
+    let mut tx = state.pool.begin().await?;
+    let current = categories::fetch(&mut *tx, user_id, id).await?;
+
+    if let Some(name) = name {
+        let taken: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM categories WHERE user_id = ?1 AND name = ?2 AND id != ?3
+        "#,
+        )
+        .bind(user_id)
+        .bind(name.as_str())
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if taken.is_some() {
+            return Err(ApiError::Conflict(
+                "A category with that name already exists, merge into it instead",
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE categories SET name = ?1 WHERE id = ?2
+        "#,
+        )
+        .bind(name.as_str())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        categories::reassign_expenses(&mut *tx, user_id, &current.name, name.as_str()).await?;
+    }
+
+    if let Some(parent_id) = parent_id {
+        if let Some(parent_id) = parent_id {
+            categories::check_parent(&mut tx, user_id, Some(id), parent_id).await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE categories SET parent_id = ?1 WHERE id = ?2
+        "#,
+        )
+        .bind(parent_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE categories
+        SET color = CASE WHEN ?2 THEN ?3 ELSE color END,
+            icon = CASE WHEN ?4 THEN ?5 ELSE icon END
+        WHERE id = ?1
+    "#,
+    )
+    .bind(id)
+    .bind(color.is_some())
+    .bind(color.flatten().map(|color| color.into_inner()))
+    .bind(icon.is_some())
+    .bind(icon.flatten().map(|icon| icon.into_inner()))
+    .execute(&mut *tx)
+    .await?;
+
+    let category = categories::fetch(&mut *tx, user_id, id).await?;
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Category updated",
+            "category": category,
+        })),
+    ))
+}
@@ -1,6 +1,14 @@
 use crate::api::{
     AppState,
-    dto::{ApiError, Claims, ExpenseResponseDTO, UpdateRequestDTO},
+    dto::{ApiError, Claims, ExpenseRow, UpdateRequestDTO},
+    exchange_rates, expenses, merchants, tags,
+};
+use crate::domain::{
+    Currency, Description, Merchant, Notes, Tag,
+    errors::ValidationError,
+    expense_types::{parse_date, parse_time},
+    tags::MAX_TAGS_PER_EXPENSE,
+    user_types::Timezone,
 };
 use axum::{
     Json,
@@ -8,6 +16,7 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
+use chrono::Utc;
 use serde_json::json;
 
 pub async fn update_expense(
@@ -16,32 +25,160 @@ pub async fn update_expense(
     State(state): State<AppState>,
     Json(payload): Json<UpdateRequestDTO>,
 ) -> Result<impl IntoResponse, ApiError> {
-    //TODO sanitize and validate UpdateRequestDTO inputs
     let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
-    //TODO also create updated_at for created_at if update is success
-    let update: Option<ExpenseResponseDTO> = sqlx::query_as(
+
+    let expense_desc = payload
+        .expense_desc
+        .as_deref()
+        .map(Description::try_from)
+        .transpose()?;
+    // An empty merchant or notes clears it.
+    let merchant = payload
+        .merchant
+        .as_deref()
+        .map(|merchant| match merchant.trim() {
+            "" => Ok(None),
+            merchant => Merchant::try_from(merchant).map(Some),
+        })
+        .transpose()?;
+    let notes = payload
+        .notes
+        .as_deref()
+        .map(|notes| match notes.trim() {
+            "" => Ok(None),
+            notes => Notes::try_from(notes).map(Some),
+        })
+        .transpose()?;
+    let replace_tags = payload.tags.as_deref().map(Tag::parse_all).transpose()?;
+    let add_tags = Tag::parse_all(payload.add_tags.as_deref().unwrap_or_default())?;
+    let remove_tags = Tag::parse_all(payload.remove_tags.as_deref().unwrap_or_default())?;
+    let occurred_on = payload.occurred_on.as_deref().map(parse_date).transpose()?;
+    let occurred_time = payload
+        .occurred_time
+        .as_deref()
+        .map(parse_time)
+        .transpose()?;
+    let timezone = payload
+        .timezone
+        .as_deref()
+        .map(Timezone::try_from)
+        .transpose()?;
+    let currency = payload
+        .currency
+        .as_deref()
+        .map(Currency::try_from)
+        .transpose()?;
+    if currency.is_some() && payload.amount.is_none() {
+        return Err(ApiError::BadRequest(
+            "amount is required when changing the currency",
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let current_currency: Option<String> = sqlx::query_scalar(
+        r#"
+            SELECT currency FROM expenses WHERE id = ?2 AND user_id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(current_currency) = current_currency else {
+        return Ok((
+            StatusCode::NOT_FOUND,
+            Json(json!({"msg": "expense not found"})),
+        ));
+    };
+    // Without a new currency the amount is read in the one already recorded.
+    let currency = match currency {
+        Some(currency) => currency,
+        None => Currency::try_from(current_currency.as_str())?,
+    };
+    let amount = payload
+        .amount
+        .as_ref()
+        .map(|amount| amount.parse(currency))
+        .transpose()?;
+    let merchant = match merchant {
+        Some(Some(merchant)) => Some(Some(
+            merchants::resolve(&mut tx, user_id, &merchant).await?.0,
+        )),
+        merchant => merchant,
+    };
+
+    let update: Option<i64> = sqlx::query_scalar(
         r#"
             UPDATE expenses
-            SET expense_desc = COALESCE(?3, expense_desc), amount = COALESCE(?4, amount)
+            SET expense_desc = COALESCE(?3, expense_desc),
+                amount = COALESCE(?4, amount),
+                currency = COALESCE(?9, currency),
+                occurred_on = COALESCE(?6, occurred_on),
+                occurred_time = COALESCE(?7, occurred_time),
+                timezone = COALESCE(?8, timezone),
+                merchant = CASE WHEN ?10 THEN ?11 ELSE merchant END,
+                notes = CASE WHEN ?12 THEN ?13 ELSE notes END,
+                updated_at = ?5
             WHERE id = ?2 AND user_id = ?1 
-            RETURNING id, expense_desc, amount, category, created_at
+            RETURNING id
         "#,
     )
     .bind(user_id)
     .bind(id)
-    .bind(payload.expense_desc)
-    .bind(payload.amount)
-    .fetch_optional(&state.pool)
+    .bind(expense_desc.map(Description::into_inner))
+    .bind(amount.map(|amount| amount.minor_units()))
+    .bind(Utc::now().to_rfc3339())
+    .bind(occurred_on.map(|date| date.to_string()))
+    .bind(occurred_time.map(|time| time.format("%H:%M:%S").to_string()))
+    .bind(timezone.map(|timezone| timezone.name()))
+    .bind(amount.map(|amount| amount.currency().code()))
+    .bind(merchant.is_some())
+    .bind(merchant.flatten().map(Merchant::into_inner))
+    .bind(notes.is_some())
+    .bind(notes.flatten().map(Notes::into_inner))
+    .fetch_optional(&mut *tx)
     .await?;
 
-    match update {
-        Some(u) => Ok((
-            StatusCode::OK,
-            Json(json!({"msg": "expense updated successfully", "expense": u})),
-        )),
-        None => Ok((
+    if update.is_none() {
+        return Ok((
             StatusCode::NOT_FOUND,
             Json(json!({"msg": "expense not found"})),
-        )),
+        ));
+    }
+
+    // Only a change to what was spent or when re-reads the rate; anything
+    // else keeps the one frozen on the row.
+    let settings = expenses::user_settings(&mut *tx, user_id).await?;
+    let reconverted = amount.is_some() || occurred_on.is_some();
+    if reconverted {
+        exchange_rates::freeze(&mut tx, id, settings.base_currency).await?;
     }
+
+    if let Some(replace_tags) = replace_tags {
+        tags::clear(&mut tx, id).await?;
+        tags::attach(&mut tx, user_id, id, &replace_tags).await?;
+    }
+    tags::attach(&mut tx, user_id, id, &add_tags).await?;
+    tags::detach(&mut tx, user_id, id, &remove_tags).await?;
+
+    let expense = expenses::fetch(&mut *tx, user_id, id).await?;
+    if expense.tags.len() > MAX_TAGS_PER_EXPENSE {
+        return Err(ValidationError::TooManyTags.into());
+    }
+    // A large expense confirmed once stays confirmed until its amount changes.
+    if amount.is_some() {
+        settings
+            .limits
+            .check(expense.base_amount, payload.confirm)?;
+    }
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "expense updated successfully",
+            "expense": ExpenseRow::try_from(expense)?,
+        })),
+    ))
 }
@@ -0,0 +1,216 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, Claims, UpdateProfileRequestDTO, UserResponseDTO},
+    emails::send_email_change_emails,
+    exchange_rates,
+    passwords::StoredPassword,
+};
+use crate::domain::{
+    Currency,
+    money::validate_limit,
+    user_types::{Email, Timezone, UserName},
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Renames the account or changes its time zone, base currency or amount
+/// limits right away. A new email only takes effect once the
+/// link sent to it is opened, see `confirm_email_change`.
+pub async fn update_me(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateProfileRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    claims.session_id()?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let username = payload.username.map(UserName::try_from).transpose()?;
+    let email = payload.email.map(Email::try_from).transpose()?;
+    let timezone = payload
+        .timezone
+        .as_deref()
+        .map(Timezone::try_from)
+        .transpose()?;
+    let base_currency = payload
+        .base_currency
+        .as_deref()
+        .map(Currency::try_from)
+        .transpose()?;
+    let max_expense_amount = payload
+        .max_expense_amount
+        .map(|limit| limit.map(validate_limit).transpose())
+        .transpose()?;
+    let confirm_above = payload
+        .confirm_above
+        .map(|limit| limit.map(validate_limit).transpose())
+        .transpose()?;
+
+    let mut user: UserResponseDTO = sqlx::query_as(
+        r#"
+            SELECT id, username, email, email_verified_at, timezone, base_currency,
+                max_expense_amount, confirm_above, created_at
+            FROM users
+            WHERE id = ?1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(ApiError::NotFound("user not found"))?;
+
+    let new_email = email
+        .map(Email::into_inner)
+        .filter(|email| *email != user.email);
+
+    if new_email.is_some() {
+        let password = payload.current_password.ok_or(ApiError::BadRequest(
+            "current_password is required to change the email",
+        ))?;
+        let stored: StoredPassword = sqlx::query_as(
+            r#"
+                SELECT id, password_hash, password_legacy FROM users WHERE id = ?1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&state.pool)
+        .await?;
+
+        state
+            .password_hashing
+            .verify_and_upgrade(&state.pool, &stored, &password)
+            .await?
+            .ensure_verified()?;
+    }
+
+    // Every change lands together or not at all.
+    let mut tx = state.pool.begin().await?;
+
+    if let Some(username) = username {
+        user = sqlx::query_as(
+            r#"
+                UPDATE users SET username = ?2 WHERE id = ?1
+                RETURNING id, username, email, email_verified_at, timezone, base_currency,
+                    max_expense_amount, confirm_above, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(username.into_inner())
+        .fetch_one(&mut *tx)
+        .await?;
+    }
+
+    if let Some(timezone) = timezone {
+        user = sqlx::query_as(
+            r#"
+                UPDATE users SET timezone = ?2 WHERE id = ?1
+                RETURNING id, username, email, email_verified_at, timezone, base_currency,
+                    max_expense_amount, confirm_above, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(timezone.name())
+        .fetch_one(&mut *tx)
+        .await?;
+    }
+
+    // Totals are only meaningful in one currency, so every expense is
+    // converted again; a missing rate for any of them rejects the whole update.
+    // Limits are whole units of the base currency and move at today's rate.
+    if let Some(base_currency) = base_currency {
+        let limits = [user.max_expense_amount, user.confirm_above];
+        let rate = if limits.iter().any(Option::is_some) {
+            let previous = Currency::try_from(user.base_currency.as_str())?;
+            let today = Timezone::try_from(user.timezone.as_str())?.today();
+            match exchange_rates::rate(&mut *tx, previous, base_currency, today).await {
+                Err(ApiError::BadRequest(_)) => {
+                    return Err(ApiError::BadRequest(
+                        "No exchange rate to convert the amount limits into the new base currency",
+                    ));
+                }
+                rate => rate?,
+            }
+        } else {
+            1.0
+        };
+        let [max, confirm] = limits.map(|limit| limit.map(|limit| convert_limit(limit, rate)));
+
+        user = sqlx::query_as(
+            r#"
+                UPDATE users
+                SET base_currency = ?2, max_expense_amount = ?3, confirm_above = ?4
+                WHERE id = ?1
+                RETURNING id, username, email, email_verified_at, timezone, base_currency,
+                    max_expense_amount, confirm_above, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(base_currency.code())
+        .bind(max)
+        .bind(confirm)
+        .fetch_one(&mut *tx)
+        .await?;
+        exchange_rates::freeze_all(&mut tx, user_id, base_currency).await?;
+    }
+
+    if max_expense_amount.is_some() || confirm_above.is_some() {
+        user = sqlx::query_as(
+            r#"
+                UPDATE users
+                SET max_expense_amount = CASE WHEN ?2 THEN ?3 ELSE max_expense_amount END,
+                    confirm_above = CASE WHEN ?4 THEN ?5 ELSE confirm_above END
+                WHERE id = ?1
+                RETURNING id, username, email, email_verified_at, timezone, base_currency,
+                    max_expense_amount, confirm_above, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(max_expense_amount.is_some())
+        .bind(max_expense_amount.flatten())
+        .bind(confirm_above.is_some())
+        .bind(confirm_above.flatten())
+        .fetch_one(&mut *tx)
+        .await?;
+    }
+
+    if let Some(new_email) = &new_email {
+        // Only the latest requested address can be confirmed.
+        sqlx::query(
+            r#"
+                UPDATE users SET pending_email = ?2 WHERE id = ?1
+            "#,
+        )
+        .bind(user_id)
+        .bind(new_email)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    if let Some(new_email) = &new_email {
+        send_email_change_emails(&state, user_id, &user.username, &user.email, new_email).await?;
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": if new_email.is_some() {
+                "Profile updated, check your new email address to confirm the change"
+            } else {
+                "Profile updated"
+            },
+            "user": user,
+            "pending_email": new_email,
+        })),
+    ))
+}
+
+/// A whole-unit limit at `rate`, kept at least 1 so it stays a valid limit.
+fn convert_limit(limit: i64, rate: f64) -> i64 {
+    ((limit as f64 * rate).round() as i64).max(1)
+}
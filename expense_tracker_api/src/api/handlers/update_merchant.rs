@@ -0,0 +1,87 @@
+use crate::api::{
+    AppState, categories,
+    dto::{ApiError, Claims, UpdateMerchantRequestDTO},
+    merchants,
+};
+use crate::domain::{Merchant, MerchantChanges};
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Renaming also moves the merchant's existing expenses to the new name.
+pub async fn update_merchant(
+    Extension(claims): Extension<Claims>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<UpdateMerchantRequestDTO>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id: i64 = claims.sub.parse().map_err(|_| ApiError::Unauthorized)?;
+
+    let MerchantChanges {
+        name,
+        category_id,
+        aliases,
+    } = payload.try_into()?;
+
+    let mut tx = state.pool.begin().await?;
+    let current = merchants::fetch(&mut *tx, user_id, id).await?;
+
+    if let Some(name) = &name {
+        merchants::ensure_name_free(&mut tx, user_id, name, Some(id)).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE merchants SET name = ?1 WHERE id = ?2
+        "#,
+        )
+        .bind(name.as_str())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        merchants::rename_expenses(&mut *tx, user_id, &current.name, name.as_str()).await?;
+    }
+
+    if let Some(category_id) = category_id {
+        if let Some(category_id) = category_id {
+            categories::fetch(&mut *tx, user_id, category_id).await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE merchants SET category_id = ?1 WHERE id = ?2
+        "#,
+        )
+        .bind(category_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Aliases are checked against the final name, so a rename and a new
+    // alias list can be sent together.
+    let name = match name {
+        Some(name) => name,
+        None => Merchant::try_from(current.name.as_str())?,
+    };
+    let aliases = match aliases {
+        Some(aliases) => aliases,
+        None => Merchant::parse_all(&current.aliases)?,
+    };
+    merchants::set_aliases(&mut tx, user_id, id, &name, &aliases).await?;
+
+    let merchant = merchants::fetch(&mut *tx, user_id, id).await?;
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "msg": "Merchant updated",
+            "merchant": merchant,
+        })),
+    ))
+}
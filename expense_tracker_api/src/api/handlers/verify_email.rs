@@ -0,0 +1,42 @@
+use crate::api::{
+    AppState,
+    dto::{ApiError, VerifyEmailQuery},
+    tokens::{VERIFY_EMAIL_AUDIENCE, verify_email_token},
+};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Query(param): Query<VerifyEmailQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let claims = verify_email_token(&state.keyring, &param.token, VERIFY_EMAIL_AUDIENCE)?;
+    let user_id: i64 = claims
+        .sub
+        .parse()
+        .map_err(|_| ApiError::BadRequest("Invalid or expired token"))?;
+
+    // The email must still match, so links for a replaced address stop working.
+    let verified = sqlx::query(
+        r#"
+            UPDATE users
+            SET email_verified_at = COALESCE(email_verified_at, datetime('now'))
+            WHERE id = ?1 AND email = ?2
+        "#,
+    )
+    .bind(user_id)
+    .bind(&claims.email)
+    .execute(&state.pool)
+    .await?;
+
+    if verified.rows_affected() == 0 {
+        return Err(ApiError::BadRequest("Invalid or expired token"));
+    }
+
+    Ok((StatusCode::OK, Json(json!({"msg": "Email verified"}))))
+}
@@ -0,0 +1,173 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use ed25519_dalek::{SigningKey, pkcs8::DecodePrivateKey};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse,
+    },
+};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{collections::HashMap, fs, io, path::Path, sync::Arc};
+use thiserror::{self, Error};
+
+/// Key id used for the shared-secret fallback when no key directory is configured.
+const HMAC_KID: &str = "hs256";
+
+#[derive(Debug, Error)]
+pub enum KeyringError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("key {0} is not an RSA or Ed25519 private key")]
+    InvalidKey(String),
+    #[error("active key {0} not found in key directory")]
+    MissingActiveKey(String),
+}
+
+struct KeyEntry {
+    alg: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    /// Public half published on the JWKS endpoint; `None` for shared secrets.
+    jwk: Option<Jwk>,
+}
+
+/// JWT signing keys identified by `kid`. Only the active key signs; every key
+/// in the ring verifies, so a rotated-out key keeps working until its file is
+/// removed from the key directory.
+#[derive(Clone)]
+pub struct Keyring {
+    active_kid: String,
+    keys: Arc<HashMap<String, KeyEntry>>,
+}
+
+impl Keyring {
+    /// HS256 with a shared secret, for local development. Nothing is published
+    /// on the JWKS endpoint in this mode.
+    pub fn hmac(secret: &str) -> Self {
+        let entry = KeyEntry {
+            alg: Algorithm::HS256,
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            jwk: None,
+        };
+
+        Self {
+            active_kid: HMAC_KID.to_string(),
+            keys: Arc::new(HashMap::from([(HMAC_KID.to_string(), entry)])),
+        }
+    }
+
+    /// Loads every `<kid>.pem` private key (RSA for RS256, Ed25519 for EdDSA) in `dir`.
+    pub fn from_dir(dir: impl AsRef<Path>, active_kid: &str) -> Result<Self, KeyringError> {
+        let mut keys = HashMap::new();
+
+        for dir_entry in fs::read_dir(dir)? {
+            let path = dir_entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pem") {
+                continue;
+            }
+            let Some(kid) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            let pem = fs::read_to_string(&path)?;
+            let entry = load_key(kid, &pem).ok_or_else(|| KeyringError::InvalidKey(kid.into()))?;
+            keys.insert(kid.to_string(), entry);
+        }
+
+        if !keys.contains_key(active_kid) {
+            return Err(KeyringError::MissingActiveKey(active_kid.to_string()));
+        }
+
+        Ok(Self {
+            active_kid: active_kid.to_string(),
+            keys: Arc::new(keys),
+        })
+    }
+
+    /// Signs `claims` with the active key, naming it in the `kid` header.
+    pub fn encode<T: Serialize>(&self, claims: &T) -> jsonwebtoken::errors::Result<String> {
+        let key = &self.keys[&self.active_kid];
+
+        let mut header = Header::new(key.alg);
+        header.kid = Some(self.active_kid.clone());
+
+        encode(&header, claims, &key.encoding)
+    }
+
+    /// Verifies `token` with the key its header names, pinned to that key's
+    /// algorithm. Tokens without a `kid` are checked against the active key.
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        audience: Option<&str>,
+    ) -> jsonwebtoken::errors::Result<T> {
+        let header = decode_header(token)?;
+        let kid = header.kid.as_deref().unwrap_or(&self.active_kid);
+        let key = self
+            .keys
+            .get(kid)
+            .ok_or(jsonwebtoken::errors::ErrorKind::InvalidSignature)?;
+
+        let mut validation = Validation::new(key.alg);
+        if let Some(audience) = audience {
+            validation.set_audience(&[audience]);
+        }
+
+        decode::<T>(token, &key.decoding, &validation).map(|token_msg| token_msg.claims)
+    }
+
+    /// Public keys for other services to verify our tokens with.
+    pub fn jwks(&self) -> JwkSet {
+        let mut keys: Vec<Jwk> = self
+            .keys
+            .values()
+            .filter_map(|entry| entry.jwk.clone())
+            .collect();
+        keys.sort_by(|a, b| a.common.key_id.cmp(&b.common.key_id));
+
+        JwkSet { keys }
+    }
+}
+
+fn load_key(kid: &str, pem: &str) -> Option<KeyEntry> {
+    if let Ok(encoding) = EncodingKey::from_rsa_pem(pem.as_bytes()) {
+        let mut jwk = Jwk::from_encoding_key(&encoding, Algorithm::RS256).ok()?;
+        jwk.common.key_id = Some(kid.to_string());
+        jwk.common.public_key_use = Some(PublicKeyUse::Signature);
+
+        return Some(KeyEntry {
+            alg: Algorithm::RS256,
+            decoding: DecodingKey::from_jwk(&jwk).ok()?,
+            encoding,
+            jwk: Some(jwk),
+        });
+    }
+
+    // jsonwebtoken cannot derive a JWK from an Ed25519 key, so take the public
+    // half from the parsed private key instead.
+    let signing_key = SigningKey::from_pkcs8_pem(pem).ok()?;
+    let x = URL_SAFE_NO_PAD.encode(signing_key.verifying_key().as_bytes());
+
+    let jwk = Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(KeyAlgorithm::EdDSA),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+            key_type: OctetKeyPairType::OctetKeyPair,
+            curve: EllipticCurve::Ed25519,
+            x: x.clone(),
+        }),
+    };
+
+    Some(KeyEntry {
+        alg: Algorithm::EdDSA,
+        encoding: EncodingKey::from_ed_pem(pem.as_bytes()).ok()?,
+        decoding: DecodingKey::from_ed_components(&x).ok()?,
+        jwk: Some(jwk),
+    })
+}
@@ -0,0 +1,224 @@
+use crate::api::dto::{ApiError, MerchantDbRow, MerchantResponseDTO};
+use crate::domain::{Category, Merchant};
+use chrono::Utc;
+use sqlx::SqliteConnection;
+
+/// Looks `name` up among the user's merchants by name or alias and returns
+/// the merchant's own spelling with its default category. A merchant not
+/// seen before is remembered so it autocompletes next time.
+pub async fn resolve(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    name: &Merchant,
+) -> Result<(Merchant, Option<Category>), ApiError> {
+    let known: Option<(String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT m.name, c.name
+        FROM merchants m
+        LEFT JOIN categories c ON c.id = m.category_id
+        WHERE m.user_id = ?1
+            AND (m.name = ?2 OR m.id IN (
+                SELECT merchant_id FROM merchant_aliases WHERE user_id = ?1 AND alias = ?2
+            ))
+    "#,
+    )
+    .bind(user_id)
+    .bind(name.as_str())
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match known {
+        Some((name, category)) => Ok((
+            Merchant::try_from(name.as_str())?,
+            category.map(Category::try_from).transpose()?,
+        )),
+        None => {
+            sqlx::query(
+                r#"
+                INSERT INTO merchants (user_id, name) VALUES (?1, ?2)
+                ON CONFLICT (user_id, name) DO NOTHING
+            "#,
+            )
+            .bind(user_id)
+            .bind(name.as_str())
+            .execute(&mut *conn)
+            .await?;
+            Ok((name.clone(), None))
+        }
+    }
+}
+
+/// The user's merchants, most used first, with their aliases and expense
+/// counts; only `id` when given, and only those with a name or alias
+/// starting with `prefix` when given.
+pub async fn list<'e, E>(
+    executor: E,
+    user_id: i64,
+    id: Option<i64>,
+    prefix: Option<&str>,
+    limit: i64,
+) -> Result<Vec<MerchantResponseDTO>, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let pattern = prefix.map(|prefix| {
+        let escaped = prefix
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        format!("{escaped}%")
+    });
+
+    let rows: Vec<MerchantDbRow> = sqlx::query_as(
+        r#"
+        SELECT m.id, m.name, m.category_id, c.name AS category,
+            (
+                SELECT group_concat(a.alias, char(31))
+                FROM merchant_aliases a
+                WHERE a.merchant_id = m.id
+            ) AS aliases,
+            (
+                SELECT COUNT(*)
+                FROM expenses e
+                WHERE e.user_id = m.user_id AND m.name = e.merchant
+            ) AS expense_count,
+            m.created_at
+        FROM merchants m
+        LEFT JOIN categories c ON c.id = m.category_id
+        WHERE m.user_id = ?1
+            AND (?2 IS NULL OR m.id = ?2)
+            AND (
+                ?3 IS NULL
+                OR m.name LIKE ?3 ESCAPE '\'
+                OR EXISTS (
+                    SELECT 1 FROM merchant_aliases a
+                    WHERE a.merchant_id = m.id AND a.alias LIKE ?3 ESCAPE '\'
+                )
+            )
+        ORDER BY expense_count DESC, m.name
+        LIMIT ?4
+    "#,
+    )
+    .bind(user_id)
+    .bind(id)
+    .bind(pattern)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows.into_iter().map(MerchantResponseDTO::from).collect())
+}
+
+/// One of the user's merchants.
+pub async fn fetch<'e, E>(
+    executor: E,
+    user_id: i64,
+    id: i64,
+) -> Result<MerchantResponseDTO, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    list(executor, user_id, Some(id), None, 1)
+        .await?
+        .pop()
+        .ok_or(ApiError::NotFound("merchant not found"))
+}
+
+/// Names and aliases are unique across all of a user's merchants, so each
+/// one points at a single merchant.
+pub async fn ensure_name_free(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    name: &Merchant,
+    id: Option<i64>,
+) -> Result<(), ApiError> {
+    let taken: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM merchants
+            WHERE user_id = ?1 AND name = ?2 AND id IS NOT ?3
+            UNION ALL
+            SELECT 1 FROM merchant_aliases
+            WHERE user_id = ?1 AND alias = ?2 AND merchant_id IS NOT ?3
+        )
+    "#,
+    )
+    .bind(user_id)
+    .bind(name.as_str())
+    .bind(id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if taken {
+        return Err(ApiError::Conflict(
+            "Another merchant already has that name or alias",
+        ));
+    }
+    Ok(())
+}
+
+/// Replaces the merchant's aliases; an alias that is just its own name is
+/// dropped.
+pub async fn set_aliases(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    id: i64,
+    name: &Merchant,
+    aliases: &[Merchant],
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        DELETE FROM merchant_aliases WHERE merchant_id = ?1
+    "#,
+    )
+    .bind(id)
+    .execute(&mut *conn)
+    .await?;
+
+    for alias in aliases {
+        if alias.as_str().to_lowercase() == name.as_str().to_lowercase() {
+            continue;
+        }
+        ensure_name_free(conn, user_id, alias, Some(id)).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO merchant_aliases (merchant_id, user_id, alias) VALUES (?1, ?2, ?3)
+        "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(alias.as_str())
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Moves every expense at merchant `from` to `to`; returns how many moved.
+pub async fn rename_expenses<'e, E>(
+    executor: E,
+    user_id: i64,
+    from: &str,
+    to: &str,
+) -> Result<u64, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE expenses
+        SET merchant = ?3, updated_at = ?4
+        WHERE user_id = ?1 AND merchant = ?2 COLLATE NOCASE
+    "#,
+    )
+    .bind(user_id)
+    .bind(from)
+    .bind(to)
+    .bind(Utc::now().to_rfc3339())
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected())
+}
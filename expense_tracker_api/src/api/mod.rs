@@ -1,6 +1,20 @@
+pub mod audit;
 pub mod auth;
+pub mod categories;
 pub mod dto;
+pub mod emails;
+pub mod exchange_rates;
+pub mod expenses;
 pub mod handlers;
+pub mod keyring;
+pub mod merchants;
+pub mod oidc;
+pub mod pagination;
+pub mod passwords;
 pub mod state;
-pub use auth::auth;
+pub mod tags;
+pub mod throttle;
+pub mod tokens;
+pub mod totp;
+pub use auth::{auth, require_scope, require_verified_email};
 pub use state::AppState;
@@ -0,0 +1,249 @@
+use crate::api::dto::ApiError;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation, decode, decode_header,
+    jwk::{Jwk, JwkSet},
+};
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::OnceCell;
+
+/// How long a user has to finish signing in at the identity provider.
+pub const OIDC_LOGIN_TTL_MINUTES: i64 = 10;
+/// Cookie tying the callback to the browser that started the sign-in.
+pub const OIDC_STATE_COOKIE: &str = "oidc_state";
+const HTTP_TIMEOUT_SECS: u64 = 10;
+
+/// Signature algorithms accepted on ID tokens. Shared-secret algorithms are
+/// left out so a token cannot be forged with a public key as the HMAC secret.
+const ID_TOKEN_ALGORITHMS: [Algorithm; 9] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// Relying-party settings for the identity provider.
+pub struct OidcConfig {
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub redirect_url: String,
+    pub scopes: String,
+}
+
+/// Endpoints read from `<issuer>/.well-known/openid-configuration`.
+#[derive(Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// The ID token claims we act on.
+#[derive(Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub nonce: Option<String>,
+    pub azp: Option<String>,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+}
+
+/// OpenID Connect relying party using the authorization-code flow with PKCE.
+/// Discovery is fetched on first use and cached; the provider's JWKS is fetched
+/// on every verification so key rotation at the provider needs no restart.
+#[derive(Clone)]
+pub struct OidcClient {
+    config: Arc<OidcConfig>,
+    http: Client,
+    metadata: Arc<OnceCell<ProviderMetadata>>,
+}
+
+impl OidcClient {
+    pub fn new(config: OidcConfig) -> reqwest::Result<Self> {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+            .build()?;
+
+        Ok(Self {
+            config: Arc::new(config),
+            http,
+            metadata: Arc::new(OnceCell::new()),
+        })
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.config.issuer
+    }
+
+    async fn metadata(&self) -> Result<&ProviderMetadata, ApiError> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                let metadata: ProviderMetadata = self.get_json(&url).await?;
+
+                if metadata.issuer != self.config.issuer {
+                    tracing::error!(issuer = %metadata.issuer, "discovery document names another issuer");
+                    return Err(ApiError::BadGateway("identity provider is misconfigured"));
+                }
+                Ok(metadata)
+            })
+            .await
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<T, ApiError> {
+        let response = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| {
+                tracing::error!(url, error = %err, "identity provider request failed");
+                ApiError::BadGateway("identity provider is unavailable")
+            })?;
+
+        response.json().await.map_err(|err| {
+            tracing::error!(url, error = %err, "identity provider sent an unreadable response");
+            ApiError::BadGateway("identity provider is unavailable")
+        })
+    }
+
+    /// Where to send the browser to start signing in.
+    pub async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_verifier: &str,
+    ) -> Result<String, ApiError> {
+        let metadata = self.metadata().await?;
+
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            [
+                ("response_type", "code"),
+                ("client_id", &self.config.client_id),
+                ("redirect_uri", &self.config.redirect_url),
+                ("scope", &self.config.scopes),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", &pkce_challenge(code_verifier)),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|_| ApiError::BadGateway("identity provider is misconfigured"))?;
+
+        Ok(url.into())
+    }
+
+    /// Redeems the authorization code and returns the verified ID token claims.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, ApiError> {
+        let metadata = self.metadata().await?;
+
+        let mut request = self.http.post(&metadata.token_endpoint).form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.config.redirect_url),
+            ("client_id", &self.config.client_id),
+            ("code_verifier", code_verifier),
+        ]);
+        if let Some(secret) = &self.config.client_secret {
+            request = request.basic_auth(&self.config.client_id, Some(secret));
+        }
+
+        let response = request.send().await.map_err(|err| {
+            tracing::error!(error = %err, "token request to identity provider failed");
+            ApiError::BadGateway("identity provider is unavailable")
+        })?;
+        if !response.status().is_success() {
+            tracing::warn!(status = %response.status(), "identity provider rejected the authorization code");
+            return Err(ApiError::Unauthorized);
+        }
+        let tokens: TokenResponse = response
+            .json()
+            .await
+            .map_err(|_| ApiError::BadGateway("identity provider is unavailable"))?;
+
+        self.verify_id_token(&tokens.id_token, nonce).await
+    }
+
+    async fn verify_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, ApiError> {
+        let metadata = self.metadata().await?;
+
+        let header = decode_header(id_token).map_err(|_| ApiError::Unauthorized)?;
+        if !ID_TOKEN_ALGORITHMS.contains(&header.alg) {
+            return Err(ApiError::Unauthorized);
+        }
+
+        let jwks: JwkSet = self.get_json(&metadata.jwks_uri).await?;
+        let jwk = find_key(&jwks, header.kid.as_deref()).ok_or(ApiError::Unauthorized)?;
+        let key = DecodingKey::from_jwk(jwk).map_err(|_| ApiError::Unauthorized)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.config.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims: IdTokenClaims = decode(id_token, &key, &validation)
+            .map_err(|err| {
+                tracing::warn!(error = %err, "rejected ID token");
+                ApiError::Unauthorized
+            })?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(ApiError::Unauthorized);
+        }
+        if claims
+            .azp
+            .as_deref()
+            .is_some_and(|azp| azp != self.config.client_id)
+        {
+            return Err(ApiError::Unauthorized);
+        }
+
+        Ok(claims)
+    }
+}
+
+/// Picks the key named by `kid`, or the only key when the token names none.
+fn find_key<'a>(jwks: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+}
+
+/// RFC 7636 S256 code challenge.
+fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
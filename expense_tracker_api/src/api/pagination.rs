@@ -0,0 +1,80 @@
+use crate::api::{
+    dto::{ApiError, CursorClaims},
+    keyring::Keyring,
+    tokens::hash_token,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+const CURSOR_AUDIENCE: &str = "expense-cursor";
+const CURSOR_TTL_HOURS: i64 = 24;
+
+/// Value of the sort column; text for dates and categories, integer for
+/// amounts.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum SortKey {
+    Integer(i64),
+    Text(String),
+}
+
+/// Where the next page starts.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Position {
+    /// The sort key and id of the last row already returned.
+    Keyset { key: SortKey, id: i64 },
+    /// How many rows were already returned. Used for relevance order, since
+    /// search scores are floats that shift whenever anyone's expenses change
+    /// and so cannot anchor a keyset.
+    Offset { offset: i64 },
+}
+
+/// Clamps the requested page size to `1..=MAX_PAGE_SIZE`.
+pub fn page_size(requested: Option<i64>) -> i64 {
+    requested
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Signs `position` so clients cannot forge one, tied to the user and query
+/// it was issued for.
+pub fn encode_cursor(
+    keys: &Keyring,
+    user_id: i64,
+    query: &str,
+    position: Position,
+) -> Result<String, ApiError> {
+    let issued_at = Utc::now();
+    let expiration = issued_at + Duration::hours(CURSOR_TTL_HOURS);
+
+    let claims = CursorClaims {
+        sub: user_id.to_string(),
+        aud: CURSOR_AUDIENCE.to_string(),
+        position,
+        query: hash_token(query),
+        exp: expiration.timestamp(),
+        iat: issued_at.timestamp(),
+    };
+
+    Ok(keys.encode(&claims)?)
+}
+
+pub fn decode_cursor(
+    keys: &Keyring,
+    user_id: i64,
+    query: &str,
+    cursor: &str,
+) -> Result<Position, ApiError> {
+    let claims: CursorClaims = keys
+        .decode(cursor, Some(CURSOR_AUDIENCE))
+        .map_err(|_| ApiError::BadRequest("Invalid cursor"))?;
+
+    if claims.sub != user_id.to_string() || claims.query != hash_token(query) {
+        return Err(ApiError::BadRequest("Invalid cursor"));
+    }
+
+    Ok(claims.position)
+}
@@ -0,0 +1,150 @@
+use crate::api::dto::{ApiError, password_hasher};
+use crate::domain::user_types::Password;
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordVerifier, Version};
+use sqlx::{SqlitePool, prelude::FromRow};
+use std::sync::Arc;
+
+/// Argon2 context built from configuration, shared by every handler that
+/// hashes or verifies passwords.
+#[derive(Clone)]
+pub struct PasswordHashing {
+    argon2: Argon2<'static>,
+    /// Verified against when there is no real second check to make, so every
+    /// failed login costs two Argon2 runs whether or not the username exists.
+    dummy_hash: Arc<str>,
+}
+
+/// Outcome of `verify_and_upgrade`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordCheck {
+    Verified,
+    /// Only the trimmed, lowercased form matched a legacy hash. The original
+    /// casing is unknown, so the user has to pick a new password.
+    ResetRequired,
+    Failed,
+}
+
+impl PasswordCheck {
+    /// For handlers re-confirming the password of a signed-in user.
+    pub fn ensure_verified(self) -> Result<(), ApiError> {
+        match self {
+            PasswordCheck::Verified => Ok(()),
+            PasswordCheck::ResetRequired => Err(ApiError::Forbidden(PASSWORD_RESET_REQUIRED)),
+            PasswordCheck::Failed => Err(ApiError::Unauthorized),
+        }
+    }
+}
+
+pub const PASSWORD_RESET_REQUIRED: &str =
+    "Password reset required, request one via /forgot-password";
+
+/// What `verify_and_upgrade` needs to know about a stored password.
+#[derive(FromRow)]
+pub struct StoredPassword {
+    pub id: i64,
+    pub password_hash: String,
+    pub password_legacy: bool,
+}
+
+impl PasswordHashing {
+    pub fn new(params: Params) -> Result<Self, ApiError> {
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        let dummy_hash = password_hasher(&argon2, Password::new("dummy password".to_string()))?;
+
+        Ok(Self {
+            argon2,
+            dummy_hash: dummy_hash.into_inner().into(),
+        })
+    }
+
+    pub fn argon2(&self) -> &Argon2<'static> {
+        &self.argon2
+    }
+
+    pub fn hash(&self, password: Password) -> Result<Password, ApiError> {
+        password_hasher(&self.argon2, password)
+    }
+
+    pub fn verify(&self, hash: &str, plaintext: &str) -> Result<bool, ApiError> {
+        let parsed = PasswordHash::new(hash).map_err(|_| ApiError::Internal)?;
+        Ok(self
+            .argon2
+            .verify_password(plaintext.as_bytes(), &parsed)
+            .is_ok())
+    }
+
+    /// Burns the same work as a failed `verify_and_upgrade` when there is no
+    /// user to check: the exact attempt plus the legacy fallback.
+    pub fn verify_dummy(&self, plaintext: &str) {
+        let _ = self.verify(&self.dummy_hash, plaintext);
+        let _ = self.verify(&self.dummy_hash, plaintext);
+    }
+
+    /// True when `hash` was made with another algorithm or weaker parameters than configured.
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(hash) else {
+            return true;
+        };
+        if parsed.algorithm != Algorithm::Argon2id.ident()
+            || parsed.version != Some(Version::V0x13.into())
+        {
+            return true;
+        }
+        let Ok(stored) = Params::try_from(&parsed) else {
+            return true;
+        };
+
+        let current = self.argon2.params();
+        stored.m_cost() < current.m_cost()
+            || stored.t_cost() < current.t_cost()
+            || stored.p_cost() < current.p_cost()
+    }
+
+    /// Checks `plaintext` against the stored hash. Hashes created while
+    /// passwords were still trimmed and lowercased are also tried in that form,
+    /// but a match there only asks for a reset since the real casing is lost.
+    /// A failed check always costs two verifications, matching `verify_dummy`.
+    /// On an exact match any outdated hash is replaced under the current
+    /// parameters.
+    pub async fn verify_and_upgrade(
+        &self,
+        pool: &SqlitePool,
+        stored: &StoredPassword,
+        plaintext: &str,
+    ) -> Result<PasswordCheck, ApiError> {
+        if !self.verify(&stored.password_hash, plaintext)? {
+            if !stored.password_legacy {
+                let _ = self.verify(&self.dummy_hash, plaintext);
+                return Ok(PasswordCheck::Failed);
+            }
+            let folded = plaintext.trim().to_ascii_lowercase();
+            return Ok(if self.verify(&stored.password_hash, &folded)? {
+                PasswordCheck::ResetRequired
+            } else {
+                PasswordCheck::Failed
+            });
+        }
+
+        if stored.password_legacy || self.needs_rehash(&stored.password_hash) {
+            let upgraded = self.hash(Password::new(plaintext.to_string()))?;
+            let update = sqlx::query(
+                r#"
+                UPDATE users
+                SET password_hash = ?2, password_legacy = 0
+                WHERE id = ?1 AND password_hash = ?3
+            "#,
+            )
+            .bind(stored.id)
+            .bind(upgraded.into_inner())
+            .bind(&stored.password_hash)
+            .execute(pool)
+            .await;
+
+            if let Err(err) = update {
+                tracing::error!(user_id = stored.id, error = %err, "failed to rehash password");
+            }
+        }
+
+        Ok(PasswordCheck::Verified)
+    }
+}
@@ -1,7 +1,23 @@
+use crate::api::{
+    auth::UnverifiedPolicy, keyring::Keyring, oidc::OidcClient, passwords::PasswordHashing,
+    totp::SecretCipher,
+};
+use crate::domain::user_types::PasswordPolicy;
+use crate::mailer::Mailer;
 use sqlx::SqlitePool;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: SqlitePool,
-    pub jwt_secret: String,
+    pub keyring: Keyring,
+    pub totp_cipher: SecretCipher,
+    pub password_policy: PasswordPolicy,
+    pub password_hashing: PasswordHashing,
+    pub mailer: Arc<dyn Mailer>,
+    pub unverified_policy: UnverifiedPolicy,
+    /// Base URL clients reach the API on, used to build links in emails.
+    pub public_url: String,
+    /// Single sign-on provider; `None` when OIDC is not configured.
+    pub oidc: Option<OidcClient>,
 }
@@ -0,0 +1,77 @@
+use crate::api::dto::ApiError;
+use crate::domain::Tag;
+use sqlx::SqliteConnection;
+
+/// Adds `tags` to the expense, creating any the user has not used before.
+pub async fn attach(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    expense_id: i64,
+    tags: &[Tag],
+) -> Result<(), ApiError> {
+    for tag in tags {
+        sqlx::query(
+            r#"
+            INSERT INTO tags (user_id, name) VALUES (?1, ?2)
+            ON CONFLICT (user_id, name) DO NOTHING
+        "#,
+        )
+        .bind(user_id)
+        .bind(tag.as_str())
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO expense_tags (expense_id, tag_id)
+            SELECT ?1, id FROM tags WHERE user_id = ?2 AND name = ?3
+        "#,
+        )
+        .bind(expense_id)
+        .bind(user_id)
+        .bind(tag.as_str())
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Removes `tags` from the expense; tags it does not carry are ignored.
+pub async fn detach(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    expense_id: i64,
+    tags: &[Tag],
+) -> Result<(), ApiError> {
+    for tag in tags {
+        sqlx::query(
+            r#"
+            DELETE FROM expense_tags
+            WHERE expense_id = ?1
+                AND tag_id = (SELECT id FROM tags WHERE user_id = ?2 AND name = ?3)
+        "#,
+        )
+        .bind(expense_id)
+        .bind(user_id)
+        .bind(tag.as_str())
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Removes every tag from the expense.
+pub async fn clear(conn: &mut SqliteConnection, expense_id: i64) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        DELETE FROM expense_tags WHERE expense_id = ?1
+    "#,
+    )
+    .bind(expense_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
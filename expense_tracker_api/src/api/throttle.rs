@@ -0,0 +1,191 @@
+use crate::api::dto::ApiError;
+use sqlx::SqlitePool;
+
+const BASE_LOCKOUT_SECS: i64 = 15;
+const MAX_LOCKOUT_SECS: i64 = 60 * 60;
+/// Failures older than this no longer count towards a lockout.
+const FAILURE_WINDOW: &str = "-1 hour";
+
+#[derive(Clone, Copy)]
+pub enum Throttle {
+    Username,
+    Ip,
+    Totp,
+}
+
+impl Throttle {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Throttle::Username => "username",
+            Throttle::Ip => "ip",
+            Throttle::Totp => "totp",
+        }
+    }
+
+    /// Failures allowed before any lockout kicks in. IPs get more room since
+    /// many users can sit behind one address.
+    fn free_attempts(&self) -> i64 {
+        match self {
+            Throttle::Ip => 20,
+            Throttle::Username | Throttle::Totp => 5,
+        }
+    }
+
+    fn locked(&self, retry_after: u64) -> ApiError {
+        match self {
+            Throttle::Ip => ApiError::TooManyRequests { retry_after },
+            Throttle::Username | Throttle::Totp => ApiError::Locked { retry_after },
+        }
+    }
+}
+
+/// Exponential backoff: 15s, 30s, 60s, ... capped at an hour.
+fn lockout_secs(free_attempts: i64, failures: i64) -> i64 {
+    if failures < free_attempts {
+        return 0;
+    }
+    let doublings = (failures - free_attempts).min(16) as u32;
+    (BASE_LOCKOUT_SECS * 2i64.pow(doublings)).min(MAX_LOCKOUT_SECS)
+}
+
+/// Rejects the attempt while `key` is locked out.
+pub async fn ensure_allowed(
+    pool: &SqlitePool,
+    throttle: Throttle,
+    key: &str,
+) -> Result<(), ApiError> {
+    let retry_after: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT CAST((julianday(locked_until) - julianday('now')) * 86400 AS INTEGER) + 1
+        FROM login_attempts
+        WHERE scope = ?1 AND key = ?2 AND locked_until > datetime('now')
+    "#,
+    )
+    .bind(throttle.as_str())
+    .bind(key)
+    .fetch_optional(pool)
+    .await?;
+
+    match retry_after {
+        Some(secs) => Err(throttle.locked(secs.max(1) as u64)),
+        None => Ok(()),
+    }
+}
+
+pub async fn record_failure(
+    pool: &SqlitePool,
+    throttle: Throttle,
+    key: &str,
+) -> Result<(), ApiError> {
+    let failures: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO login_attempts (scope, key, failures)
+        VALUES (?1, ?2, 1)
+        ON CONFLICT (scope, key) DO UPDATE SET
+            failures = CASE
+                WHEN last_failure_at < datetime('now', ?3) THEN 1
+                ELSE failures + 1
+            END,
+            last_failure_at = datetime('now')
+        RETURNING failures
+    "#,
+    )
+    .bind(throttle.as_str())
+    .bind(key)
+    .bind(FAILURE_WINDOW)
+    .fetch_one(pool)
+    .await?;
+
+    let lockout = lockout_secs(throttle.free_attempts(), failures);
+    if lockout > 0 {
+        sqlx::query(
+            r#"
+            UPDATE login_attempts
+            SET locked_until = datetime('now', ?3)
+            WHERE scope = ?1 AND key = ?2
+        "#,
+        )
+        .bind(throttle.as_str())
+        .bind(key)
+        .bind(format!("+{lockout} seconds"))
+        .execute(pool)
+        .await?;
+
+        tracing::warn!(
+            scope = throttle.as_str(),
+            failures,
+            lockout,
+            "login locked out"
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn record_success(
+    pool: &SqlitePool,
+    throttle: Throttle,
+    key: &str,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        DELETE FROM login_attempts WHERE scope = ?1 AND key = ?2
+    "#,
+    )
+    .bind(throttle.as_str())
+    .bind(key)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        http::{StatusCode, header::RETRY_AFTER},
+        response::IntoResponse,
+    };
+
+    #[test]
+    fn no_lockout_within_free_attempts() {
+        for throttle in [Throttle::Username, Throttle::Ip, Throttle::Totp] {
+            let free = throttle.free_attempts();
+            for failures in 0..free {
+                assert_eq!(lockout_secs(free, failures), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn lockout_starts_at_the_base_and_doubles() {
+        let free = Throttle::Username.free_attempts();
+        assert_eq!(lockout_secs(free, free), BASE_LOCKOUT_SECS);
+        assert_eq!(lockout_secs(free, free + 1), 30);
+        assert_eq!(lockout_secs(free, free + 2), 60);
+        assert_eq!(lockout_secs(free, free + 7), 15 * 128);
+    }
+
+    #[test]
+    fn lockout_is_capped_at_an_hour() {
+        let free = Throttle::Username.free_attempts();
+        assert_eq!(lockout_secs(free, free + 8), MAX_LOCKOUT_SECS);
+        assert_eq!(lockout_secs(free, free + 100), MAX_LOCKOUT_SECS);
+        assert_eq!(lockout_secs(free, i64::MAX), MAX_LOCKOUT_SECS);
+    }
+
+    #[test]
+    fn ips_are_rate_limited_while_accounts_are_locked() {
+        let expected = [
+            (Throttle::Ip, StatusCode::TOO_MANY_REQUESTS),
+            (Throttle::Username, StatusCode::LOCKED),
+            (Throttle::Totp, StatusCode::LOCKED),
+        ];
+        for (throttle, status) in expected {
+            let response = throttle.locked(42).into_response();
+            assert_eq!(response.status(), status);
+            assert_eq!(response.headers()[RETRY_AFTER], "42");
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use crate::api::{
+    auth::ClientInfo,
+    dto::{ApiError, ChallengeClaims, Claims, EmailClaims},
+    keyring::Keyring,
+};
+use crate::domain::user_types::Role;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+
+pub const ACCESS_TOKEN_TTL_SECS: i64 = 60 * 60;
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+pub const CHALLENGE_TOKEN_TTL_SECS: i64 = 5 * 60;
+const CHALLENGE_AUDIENCE: &str = "login-2fa";
+pub const EMAIL_TOKEN_TTL_HOURS: i64 = 24;
+pub const VERIFY_EMAIL_AUDIENCE: &str = "verify-email";
+pub const CHANGE_EMAIL_AUDIENCE: &str = "change-email";
+/// Marks personal access tokens so `auth` can tell them apart from JWTs.
+pub const API_TOKEN_PREFIX: &str = "etk_";
+
+/// Tokens handed back to the client after a successful login or refresh.
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// Opaque random token, safe to put in URLs and JSON bodies.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+pub fn generate_session_id() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Only the SHA-256 of an opaque token is ever stored.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub fn issue_access_token(
+    keys: &Keyring,
+    user_id: i64,
+    sid: &str,
+    role: Role,
+) -> Result<String, ApiError> {
+    let issued_at = Utc::now();
+    let expiration = issued_at + Duration::seconds(ACCESS_TOKEN_TTL_SECS);
+
+    let claims = Claims {
+        sub: user_id.to_string(),
+        sid: Some(sid.to_string()),
+        role,
+        exp: expiration.timestamp(),
+        iat: issued_at.timestamp(),
+    };
+
+    Ok(keys.encode(&claims)?)
+}
+
+pub fn issue_challenge_token(keys: &Keyring, user_id: i64) -> Result<String, ApiError> {
+    let issued_at = Utc::now();
+    let expiration = issued_at + Duration::seconds(CHALLENGE_TOKEN_TTL_SECS);
+
+    let claims = ChallengeClaims {
+        sub: user_id.to_string(),
+        aud: CHALLENGE_AUDIENCE.to_string(),
+        exp: expiration.timestamp(),
+        iat: issued_at.timestamp(),
+    };
+
+    Ok(keys.encode(&claims)?)
+}
+
+/// Returns the user id a valid challenge token was issued for.
+pub fn verify_challenge_token(keys: &Keyring, token: &str) -> Result<i64, ApiError> {
+    let claims: ChallengeClaims = keys
+        .decode(token, Some(CHALLENGE_AUDIENCE))
+        .map_err(|_| ApiError::Unauthorized)?;
+
+    claims.sub.parse().map_err(|_| ApiError::Unauthorized)
+}
+
+pub fn issue_email_token(
+    keys: &Keyring,
+    user_id: i64,
+    email: &str,
+    audience: &str,
+) -> Result<String, ApiError> {
+    let issued_at = Utc::now();
+    let expiration = issued_at + Duration::hours(EMAIL_TOKEN_TTL_HOURS);
+
+    let claims = EmailClaims {
+        sub: user_id.to_string(),
+        email: email.to_string(),
+        aud: audience.to_string(),
+        exp: expiration.timestamp(),
+        iat: issued_at.timestamp(),
+    };
+
+    Ok(keys.encode(&claims)?)
+}
+
+pub fn verify_email_token(
+    keys: &Keyring,
+    token: &str,
+    audience: &str,
+) -> Result<EmailClaims, ApiError> {
+    keys.decode(token, Some(audience))
+        .map_err(|_| ApiError::BadRequest("Invalid or expired token"))
+}
+
+/// Stores a fresh refresh token for the session and returns its plaintext.
+pub async fn insert_refresh_token<'e, E>(executor: E, sid: &str) -> Result<String, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let refresh_token = generate_token();
+
+    sqlx::query(
+        r#"
+        INSERT INTO refresh_tokens (session_id, token_hash, expires_at)
+        VALUES (?1, ?2, datetime('now', ?3))
+    "#,
+    )
+    .bind(sid)
+    .bind(hash_token(&refresh_token))
+    .bind(format!("+{REFRESH_TOKEN_TTL_DAYS} days"))
+    .execute(executor)
+    .await?;
+
+    Ok(refresh_token)
+}
+
+/// Role of an account that may still sign in; disabled accounts are refused.
+pub async fn active_role<'e, E>(executor: E, user_id: i64) -> Result<Role, ApiError>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let (role, disabled): (String, bool) = sqlx::query_as(
+        r#"
+        SELECT role, disabled_at IS NOT NULL FROM users WHERE id = ?1
+    "#,
+    )
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or(ApiError::Unauthorized)?;
+
+    if disabled {
+        return Err(ApiError::Forbidden("This account is disabled"));
+    }
+
+    Role::try_from(role.as_str()).map_err(|_| ApiError::Internal)
+}
+
+/// Opens a new session (refresh family) for the user.
+pub async fn start_session(
+    pool: &SqlitePool,
+    keys: &Keyring,
+    user_id: i64,
+    client: &ClientInfo,
+) -> Result<TokenPair, ApiError> {
+    let role = active_role(pool, user_id).await?;
+    let sid = generate_session_id();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO sessions (id, user_id, user_agent, ip, last_seen_at)
+        VALUES (?1, ?2, ?3, ?4, datetime('now'))
+    "#,
+    )
+    .bind(&sid)
+    .bind(user_id)
+    .bind(&client.user_agent)
+    .bind(&client.ip)
+    .execute(&mut *tx)
+    .await?;
+
+    let refresh_token = insert_refresh_token(&mut *tx, &sid).await?;
+    tx.commit().await?;
+
+    Ok(TokenPair {
+        access_token: issue_access_token(keys, user_id, &sid, role)?,
+        refresh_token,
+    })
+}
@@ -1,69 +1,91 @@
 use crate::domain::errors::ValidationError;
 
+const NAME_MAX_LEN: usize = 30;
+const ICON_MAX_LEN: usize = 32;
+
+/// A category name. Which names exist is up to each user and is checked
+/// against their `categories` rows, not here.
 #[derive(Clone)]
-pub enum Category {
-    Food,
-    Fare,
-    Groceries,
-    Leisure,
-    Electronics,
-    Utilities,
-    Clothing,
-    Health,
-}
+pub struct Category(String);
+
+/// `#rrggbb`, stored lowercase.
+#[derive(Clone)]
+pub struct CategoryColor(String);
+
+/// Free-form icon name or emoji for clients to render.
+#[derive(Clone)]
+pub struct CategoryIcon(String);
+
 impl TryFrom<String> for Category {
     type Error = ValidationError;
     fn try_from(input: String) -> Result<Self, Self::Error> {
-        let normalized = input.trim().to_ascii_lowercase();
+        // Runs of whitespace collapse so "Eating  out" and "Eating out" are one name.
+        let normalized = input.split_whitespace().collect::<Vec<_>>().join(" ");
 
-        if normalized.is_empty() {
+        if normalized.len() < 2 || normalized.len() > NAME_MAX_LEN {
             return Err(ValidationError::InvalidCategory);
         }
-        if normalized.len() < 3 {
+        if !normalized
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '&'))
+        {
             return Err(ValidationError::InvalidCategory);
         }
-        if !normalized.chars().all(|f| f.is_ascii_alphabetic()) {
+        if !normalized.starts_with(|c: char| c.is_ascii_alphanumeric()) {
             return Err(ValidationError::InvalidCategory);
         }
-        match normalized.as_str() {
-            "food" => Ok(Category::Food),
-            "fare" => Ok(Category::Fare),
-            "groceries" => Ok(Category::Groceries),
-            "leisure" => Ok(Category::Leisure),
-            "electronics" => Ok(Category::Electronics),
-            "utilities" => Ok(Category::Utilities),
-            "clothing" => Ok(Category::Clothing),
-            "health" => Ok(Category::Health),
-            _ => Err(ValidationError::InvalidCategory),
-        }
+        Ok(Self(normalized))
     }
 }
 
 impl Category {
-    pub fn _as_str(&self) -> &'static str {
-        //TODO make case insensitive
-        match self {
-            Category::Food => "Food",
-            Category::Fare => "Fare",
-            Category::Groceries => "Groceries",
-            Category::Leisure => "Leisure",
-            Category::Electronics => "Electronics",
-            Category::Utilities => "Utilities",
-            Category::Clothing => "Clothing",
-            Category::Health => "Health",
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<&str> for CategoryColor {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let trimmed = input.trim();
+        let valid = trimmed.len() == 7
+            && trimmed.starts_with('#')
+            && trimmed[1..].chars().all(|c| c.is_ascii_hexdigit());
+
+        if !valid {
+            return Err(ValidationError::InvalidColor);
         }
+        Ok(Self(trimmed.to_ascii_lowercase()))
+    }
+}
+
+impl CategoryColor {
+    pub fn into_inner(self) -> String {
+        self.0
     }
+}
 
-    pub fn into_inner(&self) -> String {
-        match self {
-            Category::Food => String::from("Food"),
-            Category::Fare => String::from("Fare"),
-            Category::Groceries => String::from("Groceries"),
-            Category::Leisure => String::from("Leisure"),
-            Category::Electronics => String::from("Electronics"),
-            Category::Utilities => String::from("Utilities"),
-            Category::Clothing => String::from("Clothing"),
-            Category::Health => String::from("Health"),
+impl TryFrom<&str> for CategoryIcon {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let trimmed = input.trim();
+
+        if trimmed.is_empty() || trimmed.chars().count() > ICON_MAX_LEN {
+            return Err(ValidationError::InvalidIcon);
+        }
+        if trimmed.chars().any(char::is_control) {
+            return Err(ValidationError::InvalidIcon);
         }
+        Ok(Self(trimmed.to_string()))
+    }
+}
+
+impl CategoryIcon {
+    pub fn into_inner(self) -> String {
+        self.0
     }
 }
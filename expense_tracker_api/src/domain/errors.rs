@@ -16,11 +16,46 @@ pub enum ValidationError {
     InvalidAmount,
     #[error("Invalid category")]
     InvalidCategory,
+    #[error("Invalid scope")]
+    InvalidScope,
+    #[error("Invalid expiry")]
+    InvalidExpiry,
+    #[error("Password is too common")]
+    CommonPassword,
+    #[error("Invalid role")]
+    InvalidRole,
+    #[error("Invalid date")]
+    InvalidDate,
+    #[error("Invalid range")]
+    InvalidRange,
+    #[error("Invalid sort")]
+    InvalidSort,
+    #[error("Invalid colour")]
+    InvalidColor,
+    #[error("Invalid icon")]
+    InvalidIcon,
+    #[error("Invalid tag")]
+    InvalidTag,
+    #[error("Too many tags")]
+    TooManyTags,
+    #[error("Too many aliases")]
+    TooManyAliases,
+    #[error("Invalid currency")]
+    InvalidCurrency,
+    #[error("Invalid limit")]
+    InvalidLimit,
+    #[error("Invalid exchange rate")]
+    InvalidExchangeRate,
+    #[error("Invalid time")]
+    InvalidTime,
+    #[error("Invalid time zone")]
+    InvalidTimezone,
 }
 #[derive(Debug, Error)]
 pub enum DomainError {
     #[error(transparent)]
     Validation(#[from] ValidationError),
+    #[allow(dead_code)]
     #[error("username already taken")]
     Conflict,
 }
@@ -0,0 +1,54 @@
+use crate::domain::{Currency, errors::ValidationError, expense_types::parse_date};
+use chrono::NaiveDate;
+
+/// One unit of `base` buys `rate` units of `quote`, as published for `date`.
+pub struct ExchangeRate {
+    pub base: Currency,
+    pub quote: Currency,
+    pub date: NaiveDate,
+    pub rate: f64,
+}
+
+impl ExchangeRate {
+    pub fn new(
+        base: Currency,
+        quote: Currency,
+        date: NaiveDate,
+        rate: f64,
+    ) -> Result<Self, ValidationError> {
+        if base == quote || !rate.is_finite() || rate <= 0.0 {
+            return Err(ValidationError::InvalidExchangeRate);
+        }
+        Ok(Self {
+            base,
+            quote,
+            date,
+            rate,
+        })
+    }
+
+    /// Parses `date,base,quote,rate` lines, e.g. `2026-01-05,EUR,USD,1.0843`.
+    /// Blank lines and a leading header line are skipped.
+    pub fn parse_csv(input: &str) -> Result<Vec<Self>, ValidationError> {
+        input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .enumerate()
+            .filter(|(index, line)| !(*index == 0 && line.starts_with("date")))
+            .map(|(_, line)| {
+                let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+                let [date, base, quote, rate] = fields[..] else {
+                    return Err(ValidationError::InvalidExchangeRate);
+                };
+                Self::new(
+                    Currency::try_from(base)?,
+                    Currency::try_from(quote)?,
+                    parse_date(date)?,
+                    rate.parse()
+                        .map_err(|_| ValidationError::InvalidExchangeRate)?,
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,158 @@
+use crate::domain::{
+    Category, Currency, Money, Tag, TagMatch, errors::ValidationError, expense_types::parse_date,
+};
+use chrono::NaiveDate;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Date,
+    Amount,
+    Category,
+    /// Best search match first; only for searches.
+    Relevance,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Validated filters and ordering for listing expenses.
+pub struct ExpenseQuery {
+    pub search: Option<String>,
+    pub dates: DateRange,
+    /// Empty means every category.
+    pub categories: Vec<Category>,
+    /// Only expenses recorded in this currency.
+    pub currency: Option<Currency>,
+    /// Both bounds are in the user's base currency.
+    pub min_amount: Option<Money>,
+    pub max_amount: Option<Money>,
+    /// Empty means no tag filter.
+    pub tags: Vec<Tag>,
+    pub tag_match: TagMatch,
+    pub sort: SortField,
+    pub order: SortOrder,
+}
+
+impl TryFrom<&str> for SortField {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "date" => Ok(SortField::Date),
+            "amount" => Ok(SortField::Amount),
+            "category" => Ok(SortField::Category),
+            "relevance" => Ok(SortField::Relevance),
+            _ => Err(ValidationError::InvalidSort),
+        }
+    }
+}
+
+impl SortField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortField::Date => "date",
+            SortField::Amount => "amount",
+            SortField::Category => "category",
+            SortField::Relevance => "relevance",
+        }
+    }
+
+    /// Column backing the field; only ever one of these fixed names.
+    pub fn column(&self) -> &'static str {
+        match self {
+            SortField::Date => "occurred_on",
+            // Compared in the base currency so mixed currencies sort sensibly.
+            SortField::Amount => "base_amount",
+            SortField::Category => "category",
+            SortField::Relevance => "score",
+        }
+    }
+}
+
+impl TryFrom<&str> for SortOrder {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            _ => Err(ValidationError::InvalidSort),
+        }
+    }
+}
+
+impl SortOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        }
+    }
+}
+
+/// Inclusive range of days; either end may be open.
+#[derive(Clone, Copy, Default)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Parses `YYYY-MM-DD` ends, rejecting a start after the end.
+    pub fn parse(from: Option<&str>, to: Option<&str>) -> Result<Self, ValidationError> {
+        let from = from.map(parse_date).transpose()?;
+        let to = to.map(parse_date).transpose()?;
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            return Err(ValidationError::InvalidRange);
+        }
+        Ok(Self { from, to })
+    }
+
+    /// Inclusive bounds as `YYYY-MM-DD`, to compare against `occurred_on`.
+    pub fn bounds(&self) -> (Option<String>, Option<String>) {
+        (
+            self.from.map(|from| from.to_string()),
+            self.to.map(|to| to.to_string()),
+        )
+    }
+}
+
+impl ExpenseQuery {
+    /// Canonical form of the query, used to tie a pagination cursor to it.
+    pub fn fingerprint(&self) -> String {
+        let categories: Vec<&str> = self.categories.iter().map(Category::as_str).collect();
+        let tags: Vec<&str> = self.tags.iter().map(Tag::as_str).collect();
+
+        format!(
+            "search={}&from={}&to={}&category={}&currency={}&min={}&max={}&tags={}&tag_match={}&sort={}&order={}",
+            self.search.as_deref().unwrap_or_default(),
+            self.dates
+                .from
+                .map(|date| date.to_string())
+                .unwrap_or_default(),
+            self.dates
+                .to
+                .map(|date| date.to_string())
+                .unwrap_or_default(),
+            categories.join(","),
+            self.currency
+                .map(|currency| currency.code())
+                .unwrap_or_default(),
+            self.min_amount
+                .as_ref()
+                .map(Money::minor_units)
+                .unwrap_or_default(),
+            self.max_amount
+                .as_ref()
+                .map(Money::minor_units)
+                .unwrap_or_default(),
+            tags.join(","),
+            self.tag_match.as_str(),
+            self.sort.as_str(),
+            self.order.as_str(),
+        )
+    }
+}
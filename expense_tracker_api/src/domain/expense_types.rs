@@ -1,36 +1,61 @@
 use crate::domain::errors::ValidationError;
+use chrono::{NaiveDate, NaiveTime};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
+const DESCRIPTION_MAX_GRAPHEMES: usize = 120;
+const MERCHANT_MAX_GRAPHEMES: usize = 80;
+const NOTES_MAX_GRAPHEMES: usize = 2000;
+/// Other names one merchant can be recognized by.
+pub const MAX_ALIASES_PER_MERCHANT: usize = 20;
+
+/// What the expense was for, e.g. `Coffee at Joe's`, kept as typed.
 #[derive(Clone)]
 pub struct Description(String);
-#[derive(Clone, Copy)]
-pub struct Amount(i64);
+
+/// Who was paid, e.g. `Joe's Café`.
+#[derive(Clone)]
+pub struct Merchant(String);
+
+/// Free-form text about an expense; may span several lines.
+#[derive(Clone)]
+pub struct Notes(String);
 
 impl TryFrom<&str> for Description {
     type Error = ValidationError;
     fn try_from(input: &str) -> Result<Self, Self::Error> {
-        let trimmed = input.trim().to_lowercase();
+        single_line(input, DESCRIPTION_MAX_GRAPHEMES).map(Self)
+    }
+}
+
+impl TryFrom<&str> for Merchant {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        single_line(input, MERCHANT_MAX_GRAPHEMES).map(Self)
+    }
+}
+
+impl TryFrom<&str> for Notes {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let normalized: String = input.trim().nfc().collect();
 
-        if trimmed.is_empty() {
+        if normalized.is_empty() {
             return Err(ValidationError::FieldEmpty);
         }
-        if trimmed.len() < 3 || trimmed.len() > 15 {
+        if normalized.graphemes(true).count() > NOTES_MAX_GRAPHEMES {
             return Err(ValidationError::InvalidLength);
         }
-
-        if !trimmed.chars().all(|c| c.is_alphanumeric() || c == '_') {
-            return Err(ValidationError::InvalidCharacter);
-        }
-
-        if !trimmed
+        if normalized
             .chars()
-            .next()
-            .is_some_and(|c| c.is_ascii_alphanumeric())
+            .any(|c| c.is_control() && c != '\n' && c != '\t')
         {
-            return Err(ValidationError::InvalidStartCharacter);
+            return Err(ValidationError::InvalidCharacter);
         }
-        Ok(Self(trimmed.to_string()))
+        Ok(Self(normalized))
     }
 }
+
 impl Description {
     pub fn _as_str(&self) -> &str {
         &self.0
@@ -40,17 +65,71 @@ impl Description {
     }
 }
 
-impl TryFrom<i64> for Amount {
-    type Error = ValidationError;
-    fn try_from(input: i64) -> Result<Self, Self::Error> {
-        if input <= 0 || input > 300_000 {
-            return Err(ValidationError::InvalidAmount);
+impl Merchant {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Validates a list of aliases, dropping duplicates regardless of case.
+    pub fn parse_all<S: AsRef<str>>(inputs: &[S]) -> Result<Vec<Merchant>, ValidationError> {
+        let mut aliases = inputs
+            .iter()
+            .map(|input| Merchant::try_from(input.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        aliases.sort_by_key(|alias| alias.0.to_lowercase());
+        aliases.dedup_by_key(|alias| alias.0.to_lowercase());
+
+        if aliases.len() > MAX_ALIASES_PER_MERCHANT {
+            return Err(ValidationError::TooManyAliases);
         }
-        Ok(Self(input))
+        Ok(aliases)
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
     }
 }
-impl Amount {
-    pub fn as_i64(&self) -> i64 {
+
+impl Notes {
+    pub fn into_inner(self) -> String {
         self.0
     }
 }
+
+/// NFC-normalizes a one-line text and collapses its runs of whitespace, then
+/// checks it has something readable in it and at most `max` graphemes.
+fn single_line(input: &str, max: usize) -> Result<String, ValidationError> {
+    if input.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+        return Err(ValidationError::InvalidCharacter);
+    }
+    let normalized = input
+        .nfc()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if normalized.is_empty() {
+        return Err(ValidationError::FieldEmpty);
+    }
+    if normalized.graphemes(true).count() > max {
+        return Err(ValidationError::InvalidLength);
+    }
+    if !normalized.chars().any(char::is_alphanumeric) {
+        return Err(ValidationError::InvalidFormat);
+    }
+    Ok(normalized)
+}
+
+/// Parses a `YYYY-MM-DD` date.
+pub fn parse_date(input: &str) -> Result<NaiveDate, ValidationError> {
+    NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d").map_err(|_| ValidationError::InvalidDate)
+}
+
+/// Parses a wall-clock `HH:MM` or `HH:MM:SS` time.
+pub fn parse_time(input: &str) -> Result<NaiveTime, ValidationError> {
+    let input = input.trim();
+    NaiveTime::parse_from_str(input, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(input, "%H:%M"))
+        .map_err(|_| ValidationError::InvalidTime)
+}
@@ -1,9 +1,22 @@
 pub mod categories;
 pub mod errors;
+pub mod exchange_rates;
+pub mod expense_query;
 pub mod expense_types;
+pub mod money;
+pub mod tags;
+pub mod token_types;
 pub mod user;
 pub mod user_types;
 
-pub use categories::Category;
-pub use expense_types::{Amount, Description};
-pub use user::{Expense, NewExpense, NewUser};
+pub use categories::{Category, CategoryColor, CategoryIcon};
+pub use exchange_rates::ExchangeRate;
+pub use expense_query::{DateRange, ExpenseQuery, SortField, SortOrder};
+pub use expense_types::{Description, Merchant, Notes};
+pub use money::{AmountLimits, Currency, LimitBreach, Money};
+pub use tags::{Tag, TagMatch};
+pub use token_types::{Scope, TokenName};
+pub use user::{
+    CategoryChanges, Expense, MerchantChanges, NewApiToken, NewCategory, NewExpense, NewMerchant,
+    NewUser, UserSettings,
+};
@@ -0,0 +1,384 @@
+use crate::domain::errors::ValidationError;
+use std::fmt;
+
+/// ISO 4217 codes the API accepts, each with its number of decimal places.
+const CURRENCIES: [(&str, u32); 24] = [
+    ("AUD", 2),
+    ("BHD", 3),
+    ("BRL", 2),
+    ("CAD", 2),
+    ("CHF", 2),
+    ("CNY", 2),
+    ("CZK", 2),
+    ("DKK", 2),
+    ("EUR", 2),
+    ("GBP", 2),
+    ("HKD", 2),
+    ("INR", 2),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("MXN", 2),
+    ("NOK", 2),
+    ("NZD", 2),
+    ("PLN", 2),
+    ("SEK", 2),
+    ("SGD", 2),
+    ("USD", 2),
+    ("ZAR", 2),
+];
+
+/// An ISO 4217 currency such as `EUR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Currency {
+    code: &'static str,
+    exponent: u32,
+}
+
+/// An amount held as a whole number of the currency's minor unit, e.g.
+/// cents for `USD` and yen for `JPY`.
+#[derive(Debug, Clone, Copy)]
+pub struct Money {
+    minor_units: i64,
+    currency: Currency,
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self {
+            code: "USD",
+            exponent: 2,
+        }
+    }
+}
+
+impl TryFrom<&str> for Currency {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let code = input.trim().to_ascii_uppercase();
+        CURRENCIES
+            .iter()
+            .find(|(known, _)| *known == code)
+            .map(|&(code, exponent)| Self { code, exponent })
+            .ok_or(ValidationError::InvalidCurrency)
+    }
+}
+
+impl Currency {
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Minor units in one major unit, 100 for `USD`.
+    pub fn scale(&self) -> i64 {
+        10_i64.pow(self.exponent)
+    }
+
+    /// Writes `minor_units` as a decimal with this currency's places, e.g. `4.50`.
+    pub fn format(&self, minor_units: i64) -> String {
+        let sign = if minor_units < 0 { "-" } else { "" };
+        let whole = minor_units.unsigned_abs() / self.scale() as u64;
+        let fraction = minor_units.unsigned_abs() % self.scale() as u64;
+        match self.exponent {
+            0 => format!("{sign}{whole}"),
+            places => format!(
+                "{sign}{whole}.{fraction:0places$}",
+                places = places as usize
+            ),
+        }
+    }
+}
+
+impl Money {
+    pub fn new(minor_units: i64, currency: Currency) -> Result<Self, ValidationError> {
+        if minor_units <= 0 {
+            return Err(ValidationError::InvalidAmount);
+        }
+        Ok(Self {
+            minor_units,
+            currency,
+        })
+    }
+
+    /// Wraps an amount that was computed rather than entered, such as a
+    /// conversion or a limit.
+    pub fn from_minor_units(minor_units: i64, currency: Currency) -> Self {
+        Self {
+            minor_units,
+            currency,
+        }
+    }
+
+    /// Parses a plain decimal such as `4.50` or `12`, allowing no more
+    /// decimal places than `currency` has.
+    pub fn parse(input: &str, currency: Currency) -> Result<Self, ValidationError> {
+        let input = input.trim();
+        let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+
+        let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty()
+            || !all_digits(whole)
+            || !all_digits(fraction)
+            || (input.contains('.') && fraction.is_empty())
+            || fraction.len() > currency.exponent as usize
+        {
+            return Err(ValidationError::InvalidAmount);
+        }
+
+        let padded = format!("{fraction:0<width$}", width = currency.exponent as usize);
+        let minor_units = whole
+            .parse::<i64>()
+            .ok()
+            .and_then(|whole| whole.checked_mul(currency.scale()))
+            .and_then(|whole| whole.checked_add(padded.parse::<i64>().unwrap_or(0)))
+            .ok_or(ValidationError::InvalidAmount)?;
+
+        Self::new(minor_units, currency)
+    }
+
+    pub fn minor_units(&self) -> i64 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// This amount in `to`, at `rate` units of `to` per unit of our currency,
+    /// rounded to the nearest minor unit of `to`.
+    pub fn convert(&self, rate: f64, to: Currency) -> Self {
+        let major = self.minor_units as f64 / self.currency.scale() as f64;
+        Self::from_minor_units((major * rate * to.scale() as f64).round() as i64, to)
+    }
+}
+
+/// Caps on a single expense in whole units of the user's base currency;
+/// `None` leaves that cap off.
+#[derive(Clone, Copy, Default)]
+pub struct AmountLimits {
+    /// Nothing above this is accepted.
+    pub max: Option<i64>,
+    /// Anything above this needs the client to confirm it.
+    pub confirm_above: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreachKind {
+    OverMaximum,
+    NeedsConfirmation,
+}
+
+/// An amount over one of the user's limits, both in the base currency.
+#[derive(Debug)]
+pub struct LimitBreach {
+    pub kind: BreachKind,
+    pub amount: Money,
+    pub limit: Money,
+}
+
+impl AmountLimits {
+    /// Checks an expense's amount in the base currency; `confirmed` lets it
+    /// past the confirmation threshold but never past the maximum.
+    pub fn check(&self, amount: Money, confirmed: bool) -> Result<(), LimitBreach> {
+        let currency = amount.currency();
+        let breach = |kind, limit: Option<i64>| {
+            let limit = Money::from_minor_units(limit?.saturating_mul(currency.scale()), currency);
+            (amount.minor_units() > limit.minor_units()).then_some(LimitBreach {
+                kind,
+                amount,
+                limit,
+            })
+        };
+
+        if let Some(breach) = breach(BreachKind::OverMaximum, self.max) {
+            return Err(breach);
+        }
+        if !confirmed
+            && let Some(breach) = breach(BreachKind::NeedsConfirmation, self.confirm_above)
+        {
+            return Err(breach);
+        }
+        Ok(())
+    }
+}
+
+/// A limit is a positive number of whole units.
+pub fn validate_limit(limit: i64) -> Result<i64, ValidationError> {
+    if limit <= 0 {
+        return Err(ValidationError::InvalidLimit);
+    }
+    Ok(limit)
+}
+
+impl fmt::Display for LimitBreach {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            BreachKind::OverMaximum => "Amount is above the largest allowed for one expense",
+            BreachKind::NeedsConfirmation => {
+                "Amount is unusually large, send it again with confirm: true if it is correct"
+            }
+        })
+    }
+}
+
+impl BreachKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreachKind::OverMaximum => "amount_over_limit",
+            BreachKind::NeedsConfirmation => "confirmation_required",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(code: &str) -> Currency {
+        Currency::try_from(code).unwrap()
+    }
+
+    fn minor_units(input: &str, code: &str) -> Option<i64> {
+        Money::parse(input, currency(code))
+            .ok()
+            .map(|money| money.minor_units())
+    }
+
+    #[test]
+    fn parses_decimals_up_to_the_currency_places() {
+        assert_eq!(minor_units("4.50", "USD"), Some(450));
+        assert_eq!(minor_units("4.5", "USD"), Some(450));
+        assert_eq!(minor_units("12", "USD"), Some(1200));
+        assert_eq!(minor_units("0.01", "USD"), Some(1));
+        assert_eq!(minor_units(" 4.50 ", "USD"), Some(450));
+        assert_eq!(minor_units("1500", "JPY"), Some(1500));
+        assert_eq!(minor_units("1.234", "BHD"), Some(1234));
+        assert_eq!(minor_units("1.2", "BHD"), Some(1200));
+    }
+
+    #[test]
+    fn rejects_more_places_than_the_currency_has() {
+        assert_eq!(minor_units("4.505", "USD"), None);
+        assert_eq!(minor_units("100.5", "JPY"), None);
+        assert_eq!(minor_units("100.0", "JPY"), None);
+        assert_eq!(minor_units("1.2345", "KWD"), None);
+    }
+
+    #[test]
+    fn rejects_anything_but_a_positive_plain_decimal() {
+        for input in [
+            "", " ", ".", "4.", ".5", "-4.50", "+4.50", "0", "0.00", "4,50", "1e3", "4.5.0", "abc",
+            "½",
+        ] {
+            assert_eq!(minor_units(input, "USD"), None, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_amounts_that_overflow() {
+        let max = i64::MAX.to_string();
+        assert_eq!(minor_units(&max, "JPY"), Some(i64::MAX));
+        assert_eq!(minor_units(&max, "USD"), None);
+        assert_eq!(minor_units("92233720368547758.08", "USD"), None);
+        assert_eq!(minor_units("99999999999999999999", "JPY"), None);
+        assert_eq!(minor_units("92233720368547758.07", "USD"), Some(i64::MAX));
+    }
+
+    #[test]
+    fn formats_with_the_currency_places() {
+        assert_eq!(currency("USD").format(450), "4.50");
+        assert_eq!(currency("USD").format(5), "0.05");
+        assert_eq!(currency("USD").format(-5), "-0.05");
+        assert_eq!(currency("JPY").format(1500), "1500");
+        assert_eq!(currency("BHD").format(1001), "1.001");
+        assert_eq!(currency("USD").format(i64::MIN), "-92233720368547758.08");
+    }
+
+    #[test]
+    fn formatting_round_trips_through_parse() {
+        for code in ["USD", "JPY", "KWD"] {
+            for minor in [1, 9, 10, 99, 100, 101, 12345, i64::MAX] {
+                let formatted = currency(code).format(minor);
+                assert_eq!(
+                    minor_units(&formatted, code),
+                    Some(minor),
+                    "{code} {formatted}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn currency_codes_are_case_insensitive_and_known() {
+        assert_eq!(currency(" eur ").code(), "EUR");
+        assert!(Currency::try_from("XYZ").is_err());
+        assert!(Currency::try_from("").is_err());
+    }
+
+    fn usd(minor_units: i64) -> Money {
+        Money::from_minor_units(minor_units, currency("USD"))
+    }
+
+    #[test]
+    fn amounts_within_the_limits_pass() {
+        let limits = AmountLimits {
+            max: Some(1000),
+            confirm_above: Some(100),
+        };
+        assert!(limits.check(usd(10_000), false).is_ok());
+        assert!(AmountLimits::default().check(usd(i64::MAX), false).is_ok());
+    }
+
+    #[test]
+    fn large_amounts_need_confirming() {
+        let limits = AmountLimits {
+            max: None,
+            confirm_above: Some(100),
+        };
+        let breach = limits.check(usd(10_001), false).unwrap_err();
+        assert_eq!(breach.kind, BreachKind::NeedsConfirmation);
+        assert_eq!(breach.amount.minor_units(), 10_001);
+        assert_eq!(breach.limit.minor_units(), 10_000);
+
+        assert!(limits.check(usd(10_001), true).is_ok());
+    }
+
+    #[test]
+    fn confirming_never_passes_the_maximum() {
+        let limits = AmountLimits {
+            max: Some(1000),
+            confirm_above: Some(100),
+        };
+        for confirmed in [false, true] {
+            let breach = limits.check(usd(100_001), confirmed).unwrap_err();
+            assert_eq!(breach.kind, BreachKind::OverMaximum);
+            assert_eq!(breach.limit.minor_units(), 100_000);
+        }
+    }
+
+    #[test]
+    fn limits_are_whole_units_of_the_base_currency() {
+        let jpy = currency("JPY");
+        let limits = AmountLimits {
+            max: Some(10_000),
+            confirm_above: None,
+        };
+        assert!(
+            limits
+                .check(Money::from_minor_units(10_000, jpy), false)
+                .is_ok()
+        );
+        assert!(
+            limits
+                .check(Money::from_minor_units(10_001, jpy), false)
+                .is_err()
+        );
+        // A limit too large to express in minor units is effectively no limit.
+        let huge = AmountLimits {
+            max: Some(i64::MAX),
+            confirm_above: None,
+        };
+        assert!(huge.check(usd(i64::MAX), false).is_ok());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::domain::errors::ValidationError;
+
+const TAG_MAX_LEN: usize = 32;
+/// Tags one expense can carry.
+pub const MAX_TAGS_PER_EXPENSE: usize = 20;
+
+/// A lowercase label such as `business-trip`; a leading `#` is accepted and
+/// dropped.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Tag(String);
+
+/// Whether an expense needs any or all of the requested tags to match.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+impl TryFrom<&str> for Tag {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let normalized = input.trim().trim_start_matches('#').to_ascii_lowercase();
+
+        if normalized.is_empty() || normalized.len() > TAG_MAX_LEN {
+            return Err(ValidationError::InvalidTag);
+        }
+        if !normalized
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(ValidationError::InvalidTag);
+        }
+        if !normalized.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            return Err(ValidationError::InvalidTag);
+        }
+        Ok(Self(normalized))
+    }
+}
+
+impl Tag {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    /// Validates a list of tags, dropping duplicates and sorting the rest.
+    pub fn parse_all<S: AsRef<str>>(inputs: &[S]) -> Result<Vec<Tag>, ValidationError> {
+        let mut tags = inputs
+            .iter()
+            .map(|input| Tag::try_from(input.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        tags.sort();
+        tags.dedup();
+
+        if tags.len() > MAX_TAGS_PER_EXPENSE {
+            return Err(ValidationError::TooManyTags);
+        }
+        Ok(tags)
+    }
+}
+
+impl TryFrom<&str> for TagMatch {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "any" => Ok(TagMatch::Any),
+            "all" => Ok(TagMatch::All),
+            _ => Err(ValidationError::InvalidTag),
+        }
+    }
+}
+
+impl TagMatch {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TagMatch::Any => "any",
+            TagMatch::All => "all",
+        }
+    }
+}
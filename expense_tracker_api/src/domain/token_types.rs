@@ -0,0 +1,69 @@
+use crate::domain::errors::ValidationError;
+
+#[derive(Clone)]
+pub struct TokenName(String);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Scope {
+    ExpensesRead,
+    ExpensesWrite,
+}
+
+impl TryFrom<String> for TokenName {
+    type Error = ValidationError;
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        let trimmed = input.trim();
+
+        if trimmed.is_empty() {
+            return Err(ValidationError::FieldEmpty);
+        }
+        if trimmed.chars().count() > 50 {
+            return Err(ValidationError::InvalidLength);
+        }
+        if trimmed.chars().any(|c| c.is_control()) {
+            return Err(ValidationError::InvalidCharacter);
+        }
+        Ok(Self(trimmed.to_string()))
+    }
+}
+
+impl TokenName {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<&str> for Scope {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "expenses:read" => Ok(Scope::ExpensesRead),
+            "expenses:write" => Ok(Scope::ExpensesWrite),
+            _ => Err(ValidationError::InvalidScope),
+        }
+    }
+}
+
+impl Scope {
+    pub const ALL: [Scope; 2] = [Scope::ExpensesRead, Scope::ExpensesWrite];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::ExpensesRead => "expenses:read",
+            Scope::ExpensesWrite => "expenses:write",
+        }
+    }
+
+    /// Parses the space separated form stored in `api_tokens.scopes`.
+    pub fn parse_list(input: &str) -> Result<Vec<Scope>, ValidationError> {
+        input.split_whitespace().map(Scope::try_from).collect()
+    }
+
+    pub fn join(scopes: &[Scope]) -> String {
+        scopes
+            .iter()
+            .map(Scope::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
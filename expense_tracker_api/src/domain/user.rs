@@ -1,5 +1,9 @@
-use crate::domain::user_types::{Email, Password, UserName};
-use crate::domain::{Amount, Category, Description};
+use crate::domain::user_types::{Email, Password, Timezone, UserName};
+use crate::domain::{
+    AmountLimits, Category, CategoryColor, CategoryIcon, Currency, Description, Merchant, Money,
+    Notes, Scope, Tag, TokenName,
+};
+use chrono::{NaiveDate, NaiveTime};
 #[derive(Clone)]
 pub struct _User {
     pub id: i64,
@@ -17,15 +21,83 @@ pub struct NewUser {
 pub struct Expense {
     pub id: i64,
     pub expense_desc: Description,
-    pub amount: Amount,
+    pub merchant: Option<Merchant>,
+    pub notes: Option<Notes>,
+    pub amount: Money,
+    /// `amount` in the user's base currency, at the rate of `occurred_on`.
+    pub base_amount: Money,
+    /// Units of the base currency per unit of `amount`'s, frozen when the
+    /// expense was recorded.
+    pub exchange_rate: f64,
     pub category: Category,
+    pub tags: Vec<Tag>,
+    /// The day the money was spent, on the calendar of `timezone`.
+    pub occurred_on: NaiveDate,
+    pub occurred_time: Option<NaiveTime>,
+    pub timezone: Timezone,
+    /// When the expense was recorded.
     pub created_at: String,
+    pub updated_at: Option<String>,
 }
 
 #[derive(Clone)]
 pub struct NewExpense {
     pub expense_desc: Description,
-    pub amount: Amount,
-    pub category: Category,
+    pub merchant: Option<Merchant>,
+    pub notes: Option<Notes>,
+    pub amount: Money,
+    /// `None` files the expense under the merchant's default category.
+    pub category: Option<Category>,
+    pub tags: Vec<Tag>,
+    pub occurred_on: NaiveDate,
+    pub occurred_time: Option<NaiveTime>,
+    pub timezone: Timezone,
+    /// Lets the amount past the user's confirmation threshold.
+    pub confirmed: bool,
     pub created_at: String,
 }
+
+/// Per-user defaults for new expenses and reports.
+pub struct UserSettings {
+    pub timezone: Timezone,
+    /// Currency every total is reported in.
+    pub base_currency: Currency,
+    /// The user's own limits, falling back to the admin defaults.
+    pub limits: AmountLimits,
+}
+
+pub struct NewCategory {
+    pub name: Category,
+    pub parent_id: Option<i64>,
+    pub color: Option<CategoryColor>,
+    pub icon: Option<CategoryIcon>,
+}
+
+/// Fields to change on a category; `Some(None)` clears the parent, colour or icon.
+pub struct CategoryChanges {
+    pub name: Option<Category>,
+    pub parent_id: Option<Option<i64>>,
+    pub color: Option<Option<CategoryColor>>,
+    pub icon: Option<Option<CategoryIcon>>,
+}
+
+pub struct NewMerchant {
+    pub name: Merchant,
+    /// Filed under this category when an expense names the merchant but no category.
+    pub category_id: Option<i64>,
+    pub aliases: Vec<Merchant>,
+}
+
+/// Fields to change on a merchant; `Some(None)` clears the default category
+/// and `aliases` replaces the whole list.
+pub struct MerchantChanges {
+    pub name: Option<Merchant>,
+    pub category_id: Option<Option<i64>>,
+    pub aliases: Option<Vec<Merchant>>,
+}
+
+pub struct NewApiToken {
+    pub name: TokenName,
+    pub scopes: Vec<Scope>,
+    pub expires_in_days: Option<i64>,
+}
@@ -1,4 +1,11 @@
 use crate::domain::errors::ValidationError;
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, io, path::Path, str::FromStr, sync::Arc};
+
+pub const PASSWORD_MIN_CHARS: usize = 8;
+pub const PASSWORD_MAX_CHARS: usize = 128;
 
 #[derive(Clone, PartialEq)]
 pub struct UserName(String);
@@ -93,17 +100,57 @@ impl Email {
     }
 }
 
+/// Plaintext passwords are kept exactly as typed: no trimming, no case folding.
 impl TryFrom<String> for Password {
     type Error = ValidationError;
 
     fn try_from(input: String) -> Result<Self, Self::Error> {
-        let trimmed = input.trim().to_ascii_lowercase();
+        if input.is_empty() {
+            return Err(ValidationError::FieldEmpty);
+        }
 
-        if trimmed.len() < 6 || trimmed.len() > 100 {
+        let chars = input.chars().count();
+        if !(PASSWORD_MIN_CHARS..=PASSWORD_MAX_CHARS).contains(&chars) {
             return Err(ValidationError::InvalidLength);
         }
 
-        Ok(Self(trimmed))
+        if input.chars().any(|c| c.is_control()) {
+            return Err(ValidationError::InvalidCharacter);
+        }
+
+        Ok(Self(input))
+    }
+}
+
+/// Rules a new password must pass on top of the basic shape checked by `Password::try_from`.
+#[derive(Clone, Default)]
+pub struct PasswordPolicy {
+    blocklist: Arc<HashSet<String>>,
+}
+
+impl PasswordPolicy {
+    /// Loads a breached/common password list, one password per line.
+    pub fn with_blocklist_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let blocklist = contents
+            .lines()
+            .map(|line| line.trim().to_lowercase())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        Ok(Self {
+            blocklist: Arc::new(blocklist),
+        })
+    }
+
+    pub fn validate(&self, input: String) -> Result<Password, ValidationError> {
+        let password = Password::try_from(input)?;
+
+        if self.blocklist.contains(&password.as_str().to_lowercase()) {
+            return Err(ValidationError::CommonPassword);
+        }
+
+        Ok(password)
     }
 }
 
@@ -118,3 +165,99 @@ impl Password {
         self.0
     }
 }
+
+/// Six digit code from an authenticator app.
+#[derive(Clone, Copy)]
+pub struct OneTimeCode(u32);
+
+impl TryFrom<&str> for OneTimeCode {
+    type Error = ValidationError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let trimmed: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if trimmed.is_empty() {
+            return Err(ValidationError::FieldEmpty);
+        }
+        if trimmed.len() != 6 {
+            return Err(ValidationError::InvalidLength);
+        }
+        if !trimmed.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ValidationError::InvalidCharacter);
+        }
+
+        trimmed
+            .parse()
+            .map(Self)
+            .map_err(|_| ValidationError::InvalidFormat)
+    }
+}
+
+impl OneTimeCode {
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+/// What an account may do beyond managing its own data.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    User,
+    Admin,
+    /// Read-only access to the admin API.
+    Auditor,
+}
+
+impl TryFrom<&str> for Role {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "user" => Ok(Role::User),
+            "admin" => Ok(Role::Admin),
+            "auditor" => Ok(Role::Auditor),
+            _ => Err(ValidationError::InvalidRole),
+        }
+    }
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+            Role::Auditor => "auditor",
+        }
+    }
+}
+
+/// An IANA time zone such as `Europe/Berlin`.
+#[derive(Clone, Copy)]
+pub struct Timezone(Tz);
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self(Tz::UTC)
+    }
+}
+
+impl TryFrom<&str> for Timezone {
+    type Error = ValidationError;
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        Tz::from_str(input.trim())
+            .map(Self)
+            .map_err(|_| ValidationError::InvalidTimezone)
+    }
+}
+
+impl Timezone {
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    /// The current date on a wall calendar in this zone.
+    pub fn today(&self) -> NaiveDate {
+        Utc::now().with_timezone(&self.0).date_naive()
+    }
+}
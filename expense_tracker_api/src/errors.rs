@@ -1,3 +1,4 @@
+use crate::api::keyring::KeyringError;
 use thiserror::{self, Error};
 
 #[derive(Debug, Error)]
@@ -10,4 +11,8 @@ pub enum AppError {
     Io(#[from] std::io::Error),
     #[error("migration error: {0}")]
     Migrate(#[from] sqlx::migrate::MigrateError),
+    #[error("keyring error: {0}")]
+    Keyring(#[from] KeyringError),
+    #[error("config error: {0}")]
+    Config(&'static str),
 }
@@ -1,5 +1,6 @@
 use crate::errors::AppError;
 use anyhow::Result;
+use argon2::Params;
 use axum::{
     Router, middleware,
     routing::{delete, get, patch, post},
@@ -8,17 +9,40 @@ use sqlx::{
     SqlitePool,
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
 };
-use std::str::FromStr;
+use std::{net::SocketAddr, str::FromStr, sync::Arc};
 use tokio::net::TcpListener;
 
 use crate::api::{
     AppState, auth,
-    handlers::{create_user, delete_expense, list_expense, login, new_expense, update_expense},
+    auth::UnverifiedPolicy,
+    exchange_rates,
+    handlers::{
+        admin_audit_log, admin_disable_user, admin_enable_user, admin_get_settings,
+        admin_import_exchange_rates, admin_list_user_expenses, admin_list_users, admin_logout_user,
+        admin_set_role, admin_update_settings, change_password, confirm_email_change, confirm_totp,
+        create_api_token, create_category, create_merchant, create_user, delete_category,
+        delete_expense, delete_me, delete_merchant, disable_totp, enroll_totp, expense_summary,
+        forgot_password, get_expense, get_me, jwks, list_api_tokens, list_categories, list_expense,
+        list_merchants, list_sessions, list_tags, login, login_two_factor, logout, merge_category,
+        new_expense, oidc_callback, oidc_login, refresh_token, resend_verification, reset_password,
+        revoke_api_token, revoke_session, update_category, update_expense, update_me,
+        update_merchant, verify_email,
+    },
+    keyring::Keyring,
+    oidc::{OidcClient, OidcConfig},
+    passwords::PasswordHashing,
+    require_scope, require_verified_email,
+    totp::SecretCipher,
 };
+use crate::domain::{ExchangeRate, Scope, user_types::PasswordPolicy};
+use crate::mailer::{FileMailer, Mailer, SmtpMailer, StdoutMailer};
 
 mod api;
 mod domain;
 mod errors;
+mod mailer;
+#[cfg(test)]
+mod tests;
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
     dotenvy::dotenv().ok();
@@ -32,32 +56,268 @@ async fn main() -> Result<(), AppError> {
         .connect_with(connect_opts)
         .await?;
 
-    let jwt_secret = std::env::var("SECRET_KEY")?;
-    let state = AppState { pool, jwt_secret };
+    let keyring = build_keyring()?;
+    let totp_cipher = build_totp_cipher()?;
+    let password_policy = match std::env::var("PASSWORD_BLOCKLIST_PATH") {
+        Ok(path) => PasswordPolicy::with_blocklist_file(path)?,
+        Err(_) => PasswordPolicy::default(),
+    };
+    let password_hashing = PasswordHashing::new(argon2_params()?)
+        .map_err(|_| AppError::Config("failed to initialise password hashing"))?;
+    let mailer = build_mailer()?;
+    let unverified_policy = unverified_policy()?;
+    let public_url = std::env::var("PUBLIC_URL")
+        .unwrap_or_else(|_| "http://localhost:3000".to_string())
+        .trim_end_matches('/')
+        .to_string();
+    let oidc = build_oidc(&public_url)?;
+    let state = AppState {
+        pool,
+        keyring,
+        totp_cipher,
+        password_policy,
+        password_hashing,
+        mailer,
+        unverified_policy,
+        public_url,
+        oidc,
+    };
 
     sqlx::migrate!("./migrations").run(&state.pool).await?;
 
+    if let Ok(path) = std::env::var("EXCHANGE_RATES_CSV") {
+        load_exchange_rates(&state.pool, path).await?;
+    }
+
+    // `BOOTSTRAP_ADMIN` names an existing account to promote, so the first
+    // admin can be created without touching the database by hand.
+    if let Ok(username) = std::env::var("BOOTSTRAP_ADMIN") {
+        sqlx::query(
+            r#"
+            UPDATE users SET role = 'admin' WHERE username = ?1
+        "#,
+        )
+        .bind(username.trim().to_ascii_lowercase())
+        .execute(&state.pool)
+        .await?;
+    }
+
     let app = build_app(state);
 
     let listener: TcpListener = TcpListener::bind("0.0.0.0:3000").await?;
 
     println!("Listening to port 3000");
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(())
+}
 
+/// Imports the `date,base,quote,rate` lines of a CSV file, so rates can be
+/// provisioned without calling the admin API.
+async fn load_exchange_rates(pool: &SqlitePool, path: String) -> Result<(), AppError> {
+    let rates = ExchangeRate::parse_csv(&std::fs::read_to_string(path)?)
+        .map_err(|_| AppError::Config("EXCHANGE_RATES_CSV has an invalid line"))?;
+    let mut conn = pool.acquire().await?;
+    exchange_rates::import(&mut conn, &rates)
+        .await
+        .map_err(|_| AppError::Config("failed to import EXCHANGE_RATES_CSV"))?;
     Ok(())
 }
 
+/// Signs with the PEM keys in `JWT_KEYS_DIR` using `JWT_ACTIVE_KID`, or falls
+/// back to HS256 with `SECRET_KEY` when no key directory is configured.
+fn build_keyring() -> Result<Keyring, AppError> {
+    match std::env::var("JWT_KEYS_DIR") {
+        Ok(dir) => {
+            let active_kid = std::env::var("JWT_ACTIVE_KID")?;
+            Ok(Keyring::from_dir(dir, &active_kid)?)
+        }
+        Err(_) => Ok(Keyring::hmac(&std::env::var("SECRET_KEY")?)),
+    }
+}
+
+/// `TOTP_ENCRYPTION_KEY` has no default: a key shipped with the code would
+/// protect nobody's TOTP secrets. Generate one with `openssl rand -base64 32`.
+fn build_totp_cipher() -> Result<SecretCipher, AppError> {
+    let key = std::env::var("TOTP_ENCRYPTION_KEY").unwrap_or_default();
+    if key.trim().is_empty() {
+        return Err(AppError::Config(
+            "TOTP_ENCRYPTION_KEY is not set, generate one with `openssl rand -base64 32`",
+        ));
+    }
+    SecretCipher::from_base64(&key).ok_or(AppError::Config(
+        "TOTP_ENCRYPTION_KEY must be 32 base64 encoded bytes",
+    ))
+}
+
+/// Argon2id cost from `ARGON2_MEMORY_KIB`, `ARGON2_ITERATIONS` and
+/// `ARGON2_PARALLELISM`, each falling back to the argon2 crate default.
+fn argon2_params() -> Result<Params, AppError> {
+    fn cost(var: &str, default: u32) -> Result<u32, AppError> {
+        match std::env::var(var) {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| AppError::Config("Argon2 cost settings must be positive integers")),
+            Err(_) => Ok(default),
+        }
+    }
+
+    Params::new(
+        cost("ARGON2_MEMORY_KIB", Params::DEFAULT_M_COST)?,
+        cost("ARGON2_ITERATIONS", Params::DEFAULT_T_COST)?,
+        cost("ARGON2_PARALLELISM", Params::DEFAULT_P_COST)?,
+        None,
+    )
+    .map_err(|_| AppError::Config("Argon2 cost settings are out of range"))
+}
+
+/// `MAILER=stdout` (default) prints emails, `MAILER=file` drops them into `MAIL_DIR`
+/// and `MAILER=smtp` relays them through `SMTP_URL` as `MAIL_FROM`.
+fn build_mailer() -> Result<Arc<dyn Mailer>, AppError> {
+    match std::env::var("MAILER").as_deref() {
+        Err(_) | Ok("stdout") => Ok(Arc::new(StdoutMailer)),
+        Ok("file") => {
+            let dir = std::env::var("MAIL_DIR").unwrap_or_else(|_| "mail".to_string());
+            Ok(Arc::new(FileMailer::new(dir)?))
+        }
+        Ok("smtp") => {
+            let url = std::env::var("SMTP_URL")?;
+            let from = std::env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "Expense Tracker <no-reply@localhost>".to_string());
+            let mailer = SmtpMailer::new(&url, &from)
+                .map_err(|_| AppError::Config("SMTP_URL or MAIL_FROM is invalid"))?;
+            Ok(Arc::new(mailer))
+        }
+        Ok(_) => Err(AppError::Config(
+            "MAILER must be one of: stdout, file, smtp",
+        )),
+    }
+}
+
+/// `UNVERIFIED_ACCOUNTS=allow|read_only|block`, read-only by default.
+fn unverified_policy() -> Result<UnverifiedPolicy, AppError> {
+    match std::env::var("UNVERIFIED_ACCOUNTS").as_deref() {
+        Ok("allow") => Ok(UnverifiedPolicy::Allow),
+        Err(_) | Ok("read_only") => Ok(UnverifiedPolicy::ReadOnly),
+        Ok("block") => Ok(UnverifiedPolicy::Block),
+        Ok(_) => Err(AppError::Config(
+            "UNVERIFIED_ACCOUNTS must be one of: allow, read_only, block",
+        )),
+    }
+}
+
+/// Single sign-on through the provider at `OIDC_ISSUER`, registered with
+/// `OIDC_CLIENT_ID` and optionally `OIDC_CLIENT_SECRET`. Disabled when unset.
+fn build_oidc(public_url: &str) -> Result<Option<OidcClient>, AppError> {
+    let Ok(issuer) = std::env::var("OIDC_ISSUER") else {
+        return Ok(None);
+    };
+
+    let config = OidcConfig {
+        issuer,
+        client_id: std::env::var("OIDC_CLIENT_ID")?,
+        client_secret: std::env::var("OIDC_CLIENT_SECRET").ok(),
+        redirect_url: std::env::var("OIDC_REDIRECT_URL")
+            .unwrap_or_else(|_| format!("{public_url}/oidc/callback")),
+        scopes: std::env::var("OIDC_SCOPES").unwrap_or_else(|_| "openid email profile".to_string()),
+    };
+
+    OidcClient::new(config)
+        .map(Some)
+        .map_err(|_| AppError::Config("failed to initialise the OIDC HTTP client"))
+}
+
 fn build_app(state: AppState) -> Router {
     let public = Router::new()
         .route("/users", post(create_user))
-        .route("/login", post(login));
+        .route("/users/verify", get(verify_email))
+        .route("/me/email/confirm", get(confirm_email_change))
+        .route("/.well-known/jwks.json", get(jwks))
+        .route("/login", post(login))
+        .route("/login/2fa", post(login_two_factor))
+        .route("/oidc/login", get(oidc_login))
+        .route("/oidc/callback", get(oidc_callback))
+        .route("/token/refresh", post(refresh_token))
+        .route("/password/forgot", post(forgot_password))
+        .route("/password/reset", post(reset_password));
 
-    let private = Router::new()
+    let expenses_read = Router::new()
+        .route("/home/expense/list", get(list_expense))
+        .route("/expenses/summary", get(expense_summary))
+        .route("/expenses/{id}", get(get_expense))
+        .route("/categories", get(list_categories))
+        .route("/tags", get(list_tags))
+        .route("/merchants", get(list_merchants))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::ExpensesRead,
+            require_scope,
+        ));
+
+    let expenses_write = Router::new()
         .route("/home/expense/delete/{id}", delete(delete_expense))
         .route("/home/expense/update/{id}", patch(update_expense))
         .route("/home/expense/add", post(new_expense))
-        .route("/home/expense/list", get(list_expense))
-        .route_layer(middleware::from_fn(auth));
+        .route("/categories", post(create_category))
+        .route(
+            "/categories/{id}",
+            patch(update_category).delete(delete_category),
+        )
+        .route("/categories/{id}/merge", post(merge_category))
+        .route("/merchants", post(create_merchant))
+        .route(
+            "/merchants/{id}",
+            patch(update_merchant).delete(delete_merchant),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::ExpensesWrite,
+            require_scope,
+        ));
+
+    let account = Router::new()
+        .route("/logout", post(logout))
+        .route("/users/verify/resend", post(resend_verification))
+        .route("/me", get(get_me).patch(update_me).delete(delete_me))
+        .route("/me/password", patch(change_password))
+        .route("/me/tokens", post(create_api_token).get(list_api_tokens))
+        .route("/me/tokens/{id}", delete(revoke_api_token))
+        .route("/me/sessions", get(list_sessions))
+        .route("/me/sessions/{id}", delete(revoke_session))
+        .route("/me/2fa", delete(disable_totp))
+        .route("/me/2fa/enroll", post(enroll_totp))
+        .route("/me/2fa/confirm", post(confirm_totp));
+
+    let admin = Router::new()
+        .route("/admin/users", get(admin_list_users))
+        .route("/admin/users/{id}/expenses", get(admin_list_user_expenses))
+        .route("/admin/users/{id}/disable", post(admin_disable_user))
+        .route("/admin/users/{id}/enable", post(admin_enable_user))
+        .route("/admin/users/{id}/logout", post(admin_logout_user))
+        .route("/admin/users/{id}/role", patch(admin_set_role))
+        .route("/admin/audit-log", get(admin_audit_log))
+        .route("/admin/exchange-rates", post(admin_import_exchange_rates))
+        .route(
+            "/admin/settings",
+            get(admin_get_settings).patch(admin_update_settings),
+        );
+
+    let expenses = Router::new()
+        .merge(expenses_read)
+        .merge(expenses_write)
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_verified_email,
+        ));
+
+    let private = Router::new()
+        .merge(expenses)
+        .merge(account)
+        .merge(admin)
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth));
 
     Router::new().merge(private).merge(public).with_state(state)
 }
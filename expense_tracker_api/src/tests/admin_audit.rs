@@ -0,0 +1,37 @@
+use super::{PASSWORD, TestApp};
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+#[tokio::test]
+async fn reading_settings_and_the_audit_log_is_audited() {
+    let app = TestApp::spawn().await;
+    app.login_new_user("ivan").await;
+    sqlx::query("UPDATE users SET role = 'admin' WHERE username = 'ivan'")
+        .execute(&app.pool)
+        .await
+        .unwrap();
+    let (_, login) = app
+        .request(
+            Method::POST,
+            "/login",
+            None,
+            Some(json!({"username": "ivan", "password": PASSWORD})),
+        )
+        .await;
+    let token = login["token"].as_str();
+
+    let (status, _) = app
+        .request(Method::GET, "/admin/settings", token, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .request(Method::GET, "/admin/audit-log", token, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let actions: Vec<String> = sqlx::query_scalar("SELECT action FROM audit_log ORDER BY id")
+        .fetch_all(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(actions, ["view_settings", "view_audit_log"]);
+}
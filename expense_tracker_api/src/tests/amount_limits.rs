@@ -0,0 +1,80 @@
+use super::TestApp;
+use reqwest::{Method, StatusCode};
+use serde_json::{Value, json};
+
+async fn add_expense(app: &TestApp, token: Option<&str>, body: Value) -> (StatusCode, Value) {
+    app.request(Method::POST, "/home/expense/add", token, Some(body))
+        .await
+}
+
+async fn update_expense(
+    app: &TestApp,
+    token: Option<&str>,
+    id: &Value,
+    body: Value,
+) -> (StatusCode, Value) {
+    app.request(
+        Method::PATCH,
+        &format!("/home/expense/update/{id}"),
+        token,
+        Some(body),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn large_expenses_need_confirming_once() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("grace").await;
+    let token = login["token"].as_str();
+
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            "/me",
+            token,
+            Some(json!({"confirm_above": 100, "max_expense_amount": 1000})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let laptop = json!({
+        "expense_desc": "laptop stand",
+        "amount": "150",
+        "category": "Food",
+        "occurred_on": "2026-10-01",
+    });
+    let (status, body) = add_expense(&app, token, laptop.clone()).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "confirmation_required");
+    assert_eq!(body["limit"]["minor_units"], 10000);
+
+    let mut confirmed = laptop;
+    confirmed["confirm"] = json!(true);
+    let (status, body) = add_expense(&app, token, confirmed).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = &body["expense"]["id"];
+
+    // Moving the date or editing the description is not a new amount.
+    let (status, _) = update_expense(
+        &app,
+        token,
+        id,
+        json!({"occurred_on": "2026-10-02", "expense_desc": "monitor stand"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = update_expense(&app, token, id, json!({"amount": "160"})).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "confirmation_required");
+
+    let (status, _) =
+        update_expense(&app, token, id, json!({"amount": "160", "confirm": true})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) =
+        update_expense(&app, token, id, json!({"amount": "1200", "confirm": true})).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "amount_over_limit");
+}
@@ -0,0 +1,88 @@
+use super::{PASSWORD, TestApp};
+use crate::api::passwords::PasswordHashing;
+use crate::domain::user_types::Password;
+use argon2::Params;
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+/// Stores `PASSWORD` the way it was hashed before casing was preserved.
+async fn make_legacy(app: &TestApp, username: &str) -> String {
+    let params = Params::new(Params::MIN_M_COST, Params::MIN_T_COST, 1, None).unwrap();
+    let folded = Password::new(PASSWORD.trim().to_ascii_lowercase());
+    let hash = PasswordHashing::new(params)
+        .unwrap()
+        .hash(folded)
+        .unwrap()
+        .into_inner();
+    sqlx::query("UPDATE users SET password_hash = ?2, password_legacy = 1 WHERE username = ?1")
+        .bind(username)
+        .bind(&hash)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+    hash
+}
+
+async fn stored(app: &TestApp, username: &str) -> (String, bool) {
+    sqlx::query_as("SELECT password_hash, password_legacy FROM users WHERE username = ?1")
+        .bind(username)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn a_case_folded_match_asks_for_a_reset_without_rehashing() {
+    let app = TestApp::spawn().await;
+    app.login_new_user("legacy").await;
+    let hash = make_legacy(&app, "legacy").await;
+
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/login",
+            None,
+            Some(json!({"username": "legacy", "password": PASSWORD})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(stored(&app, "legacy").await, (hash, true));
+}
+
+#[tokio::test]
+async fn an_exact_match_on_a_legacy_hash_logs_in_and_rehashes() {
+    let app = TestApp::spawn().await;
+    app.login_new_user("legacy").await;
+    let hash = make_legacy(&app, "legacy").await;
+
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/login",
+            None,
+            Some(json!({"username": "legacy", "password": PASSWORD.to_ascii_lowercase()})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (new_hash, legacy) = stored(&app, "legacy").await;
+    assert_ne!(new_hash, hash);
+    assert!(!legacy);
+}
+
+#[tokio::test]
+async fn a_wrong_password_on_a_legacy_hash_is_rejected() {
+    let app = TestApp::spawn().await;
+    app.login_new_user("legacy").await;
+    make_legacy(&app, "legacy").await;
+
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/login",
+            None,
+            Some(json!({"username": "legacy", "password": "Wrong-Horse-42"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
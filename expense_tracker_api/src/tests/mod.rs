@@ -0,0 +1,179 @@
+//! End-to-end tests: each one runs the full router against its own SQLite
+//! file on an ephemeral port and talks to it over HTTP.
+
+mod admin_audit;
+mod amount_limits;
+mod legacy_password;
+mod oidc;
+mod password_reset;
+mod refresh_token;
+mod search;
+mod totp;
+mod update_me;
+
+use crate::api::{
+    AppState, auth::UnverifiedPolicy, keyring::Keyring, oidc::OidcClient,
+    passwords::PasswordHashing, tokens::generate_session_id, totp::SecretCipher,
+};
+use crate::build_app;
+use crate::domain::user_types::PasswordPolicy;
+use crate::mailer::{MailError, Mailer, OutgoingEmail};
+use argon2::Params;
+use axum::Router;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use reqwest::{Method, StatusCode};
+use serde_json::Value;
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use std::{net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc};
+use tokio::net::TcpListener;
+
+/// Password of every account made by `login_new_user`.
+pub const PASSWORD: &str = "Correct-Horse-42";
+
+/// Throws every email away.
+struct NullMailer;
+
+impl Mailer for NullMailer {
+    fn send(&self, _email: &OutgoingEmail) -> Result<(), MailError> {
+        Ok(())
+    }
+}
+
+pub struct TestApp {
+    pub url: String,
+    pub pool: SqlitePool,
+    pub client: reqwest::Client,
+    db_path: PathBuf,
+}
+
+impl TestApp {
+    pub async fn spawn() -> Self {
+        Self::spawn_with_oidc(|_| None).await
+    }
+
+    /// `oidc` gets the app's own base URL, for the redirect URL.
+    pub async fn spawn_with_oidc(oidc: impl FnOnce(&str) -> Option<OidcClient>) -> Self {
+        let db_path =
+            std::env::temp_dir().join(format!("expense-test-{}.db", generate_session_id()));
+        let connect_opts =
+            SqliteConnectOptions::from_str(&format!("sqlite://{}?mode=rwc", db_path.display()))
+                .unwrap()
+                .foreign_keys(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(connect_opts)
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let (listener, url) = bind().await;
+
+        // Cheapest Argon2 cost so signing up does not dominate the run.
+        let params = Params::new(Params::MIN_M_COST, Params::MIN_T_COST, 1, None).unwrap();
+        let state = AppState {
+            pool: pool.clone(),
+            keyring: Keyring::hmac("test secret"),
+            totp_cipher: SecretCipher::from_base64(&STANDARD.encode([7u8; 32])).unwrap(),
+            password_policy: PasswordPolicy::default(),
+            password_hashing: PasswordHashing::new(params).unwrap(),
+            mailer: Arc::new(NullMailer),
+            unverified_policy: UnverifiedPolicy::Allow,
+            public_url: url.clone(),
+            oidc: oidc(&url),
+        };
+
+        serve(listener, build_app(state));
+
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap();
+
+        Self {
+            url,
+            pool,
+            client,
+            db_path,
+        }
+    }
+
+    /// Sends `body` as JSON, with `token` as the bearer token when given.
+    pub async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut request = self.client.request(method, format!("{}{path}", self.url));
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request.send().await.unwrap();
+        let status = response.status();
+        (status, response.json().await.unwrap_or(Value::Null))
+    }
+
+    /// Signs a new account up and logs it in, returning the login response.
+    pub async fn login_new_user(&self, username: &str) -> Value {
+        let (status, _) = self
+            .request(
+                Method::POST,
+                "/users",
+                None,
+                Some(serde_json::json!({
+                    "username": username,
+                    "email": format!("{username}@example.com"),
+                    "password": PASSWORD,
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, body) = self
+            .request(
+                Method::POST,
+                "/login",
+                None,
+                Some(serde_json::json!({"username": username, "password": PASSWORD})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        body
+    }
+}
+
+/// A listener on an ephemeral local port, with its base URL.
+pub async fn bind() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    (listener, url)
+}
+
+/// Serves `router` in the background for the rest of the test.
+pub fn serve(listener: TcpListener, router: Router) {
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut path = self.db_path.clone().into_os_string();
+            path.push(suffix);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
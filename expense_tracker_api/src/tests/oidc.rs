@@ -0,0 +1,402 @@
+//! Sign-in against a mock identity provider serving discovery, JWKS and the
+//! token endpoint from a local axum app.
+
+use super::{TestApp, bind, serve};
+use crate::api::{
+    keyring::Keyring,
+    oidc::{OidcClient, OidcConfig},
+    tokens::generate_session_id,
+};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use axum::{
+    Form, Json, Router,
+    extract::{Query, State},
+    http::{StatusCode as AxumStatus, header::LOCATION},
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use ed25519_dalek::{
+    SigningKey,
+    pkcs8::{EncodePrivateKey, spki::der::pem::LineEnding},
+};
+use reqwest::{
+    Method, StatusCode, Url,
+    header::{COOKIE, SET_COOKIE},
+};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+const CLIENT_ID: &str = "expense-tracker";
+
+/// Who the mock provider signs in as.
+#[derive(Clone)]
+struct Identity {
+    sub: String,
+    email: String,
+    email_verified: bool,
+}
+
+/// What `/authorize` was asked for, kept until the code is redeemed.
+struct PendingCode {
+    nonce: String,
+    code_challenge: String,
+    redirect_uri: String,
+}
+
+struct IdpState {
+    issuer: String,
+    keys: Keyring,
+    identity: Identity,
+    /// Signs ID tokens with this nonce instead of the one asked for.
+    nonce_override: Option<String>,
+    codes: HashMap<String, PendingCode>,
+}
+
+#[derive(Clone)]
+struct MockIdp(Arc<Mutex<IdpState>>);
+
+impl MockIdp {
+    async fn spawn(identity: Identity) -> (Self, String) {
+        let key_dir = std::env::temp_dir().join(format!("mock-idp-{}", generate_session_id()));
+        std::fs::create_dir_all(&key_dir).unwrap();
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        let pem = SigningKey::from_bytes(&seed)
+            .to_pkcs8_pem(LineEnding::LF)
+            .unwrap();
+        std::fs::write(key_dir.join("idp.pem"), pem.as_bytes()).unwrap();
+        let keys = Keyring::from_dir(&key_dir, "idp").unwrap();
+        std::fs::remove_dir_all(&key_dir).unwrap();
+
+        let idp = MockIdp(Arc::new(Mutex::new(IdpState {
+            issuer: String::new(),
+            keys,
+            identity,
+            nonce_override: None,
+            codes: HashMap::new(),
+        })));
+        let (listener, issuer) = bind().await;
+        idp.0.lock().unwrap().issuer = issuer.clone();
+        let router = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/authorize", get(authorize))
+            .route("/token", post(token))
+            .with_state(idp.clone());
+
+        serve(listener, router);
+        (idp, issuer)
+    }
+
+    fn sign_in_as(&self, identity: Identity) {
+        self.0.lock().unwrap().identity = identity;
+    }
+}
+
+async fn discovery(State(idp): State<MockIdp>) -> Json<Value> {
+    let issuer = idp.0.lock().unwrap().issuer.clone();
+    Json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{issuer}/authorize"),
+        "token_endpoint": format!("{issuer}/token"),
+        "jwks_uri": format!("{issuer}/jwks"),
+    }))
+}
+
+async fn jwks(State(idp): State<MockIdp>) -> Json<Value> {
+    Json(json!(idp.0.lock().unwrap().keys.jwks()))
+}
+
+/// Approves every request straight away, as if the user had consented.
+async fn authorize(
+    State(idp): State<MockIdp>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    if params.get("response_type").map(String::as_str) != Some("code")
+        || params.get("client_id").map(String::as_str) != Some(CLIENT_ID)
+        || params.get("code_challenge_method").map(String::as_str) != Some("S256")
+    {
+        return AxumStatus::BAD_REQUEST.into_response();
+    }
+
+    let code = generate_session_id();
+    let redirect_uri = params["redirect_uri"].clone();
+    let mut location = Url::parse(&redirect_uri).unwrap();
+    location
+        .query_pairs_mut()
+        .append_pair("code", &code)
+        .append_pair("state", &params["state"]);
+
+    idp.0.lock().unwrap().codes.insert(
+        code,
+        PendingCode {
+            nonce: params["nonce"].clone(),
+            code_challenge: params["code_challenge"].clone(),
+            redirect_uri,
+        },
+    );
+    Redirect::to(location.as_str()).into_response()
+}
+
+/// Redeems a code once, only with the verifier matching its PKCE challenge.
+async fn token(State(idp): State<MockIdp>, Form(form): Form<HashMap<String, String>>) -> Response {
+    let mut idp = idp.0.lock().unwrap();
+    let field = |name: &str| form.get(name).map(String::as_str).unwrap_or_default();
+
+    let Some(pending) = idp.codes.remove(field("code")) else {
+        return invalid_grant();
+    };
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(field("code_verifier").as_bytes()));
+    if field("grant_type") != "authorization_code"
+        || field("client_id") != CLIENT_ID
+        || field("redirect_uri") != pending.redirect_uri
+        || challenge != pending.code_challenge
+    {
+        return invalid_grant();
+    }
+
+    let now = Utc::now().timestamp();
+    let id_token = idp
+        .keys
+        .encode(&json!({
+            "iss": idp.issuer,
+            "aud": CLIENT_ID,
+            "sub": idp.identity.sub,
+            "email": idp.identity.email,
+            "email_verified": idp.identity.email_verified,
+            "nonce": idp.nonce_override.clone().unwrap_or(pending.nonce),
+            "iat": now,
+            "exp": now + 300,
+        }))
+        .unwrap();
+
+    Json(json!({
+        "access_token": "mock-access-token",
+        "token_type": "Bearer",
+        "id_token": id_token,
+    }))
+    .into_response()
+}
+
+fn invalid_grant() -> Response {
+    (
+        AxumStatus::BAD_REQUEST,
+        Json(json!({"error": "invalid_grant"})),
+    )
+        .into_response()
+}
+
+fn identity(sub: &str, email: &str) -> Identity {
+    Identity {
+        sub: sub.to_string(),
+        email: email.to_string(),
+        email_verified: true,
+    }
+}
+
+async fn spawn_with_idp(identity: Identity) -> (TestApp, MockIdp) {
+    let (idp, issuer) = MockIdp::spawn(identity).await;
+    let app = TestApp::spawn_with_oidc(|url| {
+        Some(
+            OidcClient::new(OidcConfig {
+                issuer,
+                client_id: CLIENT_ID.to_string(),
+                client_secret: None,
+                redirect_url: format!("{url}/oidc/callback"),
+                scopes: "openid email profile".to_string(),
+            })
+            .unwrap(),
+        )
+    })
+    .await;
+    (app, idp)
+}
+
+/// Plays the browser up to the callback: starts a sign-in, lets the provider
+/// approve it and returns the callback URL with the state cookie.
+async fn start_sign_in(app: &TestApp) -> (String, String) {
+    let response = app
+        .client
+        .get(format!("{}/oidc/login", app.url))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_redirection());
+    let cookie = response.headers()[SET_COOKIE]
+        .to_str()
+        .unwrap()
+        .split(';')
+        .next()
+        .unwrap()
+        .to_string();
+    let authorize_url = response.headers()[LOCATION].to_str().unwrap().to_string();
+
+    let response = app.client.get(authorize_url).send().await.unwrap();
+    assert!(response.status().is_redirection());
+    let callback_url = response.headers()[LOCATION].to_str().unwrap().to_string();
+
+    (callback_url, cookie)
+}
+
+async fn finish_sign_in(app: &TestApp, callback_url: &str, cookie: &str) -> (StatusCode, Value) {
+    let response = app
+        .client
+        .get(callback_url)
+        .header(COOKIE, cookie)
+        .send()
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, response.json().await.unwrap_or(Value::Null))
+}
+
+async fn sign_in(app: &TestApp) -> (StatusCode, Value) {
+    let (callback_url, cookie) = start_sign_in(app).await;
+    finish_sign_in(app, &callback_url, &cookie).await
+}
+
+async fn user_id(app: &TestApp, login: &Value) -> i64 {
+    let (status, me) = app
+        .request(Method::GET, "/me", login["token"].as_str(), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    me["user"]["id"].as_i64().unwrap()
+}
+
+async fn count(app: &TestApp, table: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+        .fetch_one(&app.pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn sign_in_round_trip_provisions_then_links_the_account() {
+    let (app, _idp) = spawn_with_idp(identity("subject-1", "dana@example.com")).await;
+
+    let (status, first) = sign_in(&app).await;
+    assert_eq!(status, StatusCode::OK, "{first}");
+    let first_id = user_id(&app, &first).await;
+
+    let (status, second) = sign_in(&app).await;
+    assert_eq!(status, StatusCode::OK, "{second}");
+    assert_eq!(user_id(&app, &second).await, first_id);
+
+    assert_eq!(count(&app, "users").await, 1);
+    assert_eq!(count(&app, "user_identities").await, 1);
+    assert_eq!(count(&app, "oidc_logins").await, 0);
+}
+
+#[tokio::test]
+async fn a_verified_email_links_the_existing_account() {
+    let (app, idp) = spawn_with_idp(identity("subject-1", "unused@example.com")).await;
+    let login = app.login_new_user("erin").await;
+    let erin = user_id(&app, &login).await;
+    sqlx::query("UPDATE users SET email_verified_at = datetime('now') WHERE id = ?1")
+        .bind(erin)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    idp.sign_in_as(identity("subject-2", "erin@example.com"));
+    let (status, first) = sign_in(&app).await;
+    assert_eq!(status, StatusCode::OK, "{first}");
+    assert_eq!(user_id(&app, &first).await, erin);
+
+    // Later sign-ins find the account through the stored identity.
+    sqlx::query("UPDATE users SET email = 'erin@elsewhere.com' WHERE id = ?1")
+        .bind(erin)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+    let (status, second) = sign_in(&app).await;
+    assert_eq!(status, StatusCode::OK, "{second}");
+    assert_eq!(user_id(&app, &second).await, erin);
+
+    let linked: i64 =
+        sqlx::query_scalar("SELECT user_id FROM user_identities WHERE subject = 'subject-2'")
+            .fetch_one(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(linked, erin);
+    assert_eq!(count(&app, "users").await, 1);
+}
+
+#[tokio::test]
+async fn an_email_only_assumed_verified_by_the_migration_is_not_linked() {
+    let (app, idp) = spawn_with_idp(identity("subject-1", "unused@example.com")).await;
+    let login = app.login_new_user("gail").await;
+    let gail = user_id(&app, &login).await;
+    sqlx::query(
+        "UPDATE users SET email_verified_at = created_at, email_verified_legacy = 1 WHERE id = ?1",
+    )
+    .bind(gail)
+    .execute(&app.pool)
+    .await
+    .unwrap();
+
+    idp.sign_in_as(identity("subject-2", "gail@example.com"));
+    let (status, login) = sign_in(&app).await;
+    assert_eq!(status, StatusCode::OK, "{login}");
+    assert_ne!(user_id(&app, &login).await, gail);
+    assert_eq!(count(&app, "users").await, 2);
+}
+
+#[tokio::test]
+async fn the_callback_needs_the_state_from_the_same_browser() {
+    let (app, _idp) = spawn_with_idp(identity("subject-1", "fay@example.com")).await;
+
+    let (callback_url, _) = start_sign_in(&app).await;
+    let (_, other_cookie) = start_sign_in(&app).await;
+    let (status, _) = finish_sign_in(&app, &callback_url, &other_cookie).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = app
+        .request(Method::GET, "/oidc/callback?code=x&state=y", None, None)
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(count(&app, "users").await, 0);
+}
+
+#[tokio::test]
+async fn a_state_is_single_use() {
+    let (app, _idp) = spawn_with_idp(identity("subject-1", "gil@example.com")).await;
+
+    let (callback_url, cookie) = start_sign_in(&app).await;
+    let (status, _) = finish_sign_in(&app, &callback_url, &cookie).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = finish_sign_in(&app, &callback_url, &cookie).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn an_id_token_with_another_nonce_is_rejected() {
+    let (app, idp) = spawn_with_idp(identity("subject-1", "hal@example.com")).await;
+    idp.0.lock().unwrap().nonce_override = Some("someone else's nonce".to_string());
+
+    let (status, _) = sign_in(&app).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(count(&app, "users").await, 0);
+}
+
+#[tokio::test]
+async fn the_code_is_only_redeemed_with_the_matching_verifier() {
+    let (app, _idp) = spawn_with_idp(identity("subject-1", "ida@example.com")).await;
+
+    let (callback_url, cookie) = start_sign_in(&app).await;
+    sqlx::query("UPDATE oidc_logins SET code_verifier = 'not-the-verifier'")
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    let (status, _) = finish_sign_in(&app, &callback_url, &cookie).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(count(&app, "users").await, 0);
+}
@@ -0,0 +1,86 @@
+use super::{PASSWORD, TestApp};
+use crate::api::tokens::hash_token;
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+#[tokio::test]
+async fn a_reset_revokes_api_tokens() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("judy").await;
+    let token = login["token"].as_str();
+
+    let (status, created) = app
+        .request(
+            Method::POST,
+            "/me/tokens",
+            token,
+            Some(json!({"name": "script", "scopes": ["expenses:read"]})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let api_token = created["token"].as_str();
+
+    let (status, _) = app
+        .request(Method::GET, "/home/expense/list", api_token, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The emailed token is thrown away by the test mailer, so plant one.
+    sqlx::query(
+        r#"
+        INSERT INTO password_reset_tokens (user_id, token_hash, expires_at)
+        SELECT id, ?1, datetime('now', '+1 hour') FROM users WHERE username = 'judy'
+    "#,
+    )
+    .bind(hash_token("reset-token"))
+    .execute(&app.pool)
+    .await
+    .unwrap();
+
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/password/reset",
+            None,
+            Some(json!({"token": "reset-token", "new_password": "Battery-Staple-77"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, _) = app
+        .request(Method::GET, "/home/expense/list", api_token, None)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn change_password_guesses_are_throttled() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("kim").await;
+    let token = login["token"].as_str();
+
+    for _ in 0..5 {
+        let (status, _) = app
+            .request(
+                Method::PATCH,
+                "/me/password",
+                token,
+                Some(
+                    json!({"old_password": "Wrong-Horse-42", "new_password": "Battery-Staple-77"}),
+                ),
+            )
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    // Locked out even with the right password until the backoff passes.
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            "/me/password",
+            token,
+            Some(json!({"old_password": PASSWORD, "new_password": "Battery-Staple-77"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::LOCKED);
+}
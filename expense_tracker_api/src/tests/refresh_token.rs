@@ -0,0 +1,65 @@
+use super::TestApp;
+use reqwest::{Method, StatusCode};
+use serde_json::{Value, json};
+
+async fn refresh(app: &TestApp, refresh_token: &Value) -> (StatusCode, Value) {
+    app.request(
+        Method::POST,
+        "/token/refresh",
+        None,
+        Some(json!({"refresh_token": refresh_token})),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn refresh_rotates_the_refresh_token() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("rotation").await;
+
+    let (status, first) = refresh(&app, &login["refresh_token"]).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(first["refresh_token"], login["refresh_token"]);
+
+    let (status, second) = refresh(&app, &first["refresh_token"]).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app
+        .request(Method::GET, "/me", second["token"].as_str(), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn reusing_a_refresh_token_revokes_the_whole_session() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("replay").await;
+
+    let (status, rotated) = refresh(&app, &login["refresh_token"]).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = refresh(&app, &login["refresh_token"]).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // The token handed out by the legitimate rotation is dead too.
+    let (status, _) = refresh(&app, &rotated["refresh_token"]).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // So is every access token issued for the session.
+    for access_token in [&login["token"], &rotated["token"]] {
+        let (status, _) = app
+            .request(Method::GET, "/me", access_token.as_str(), None)
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    let live_sessions: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM sessions WHERE revoked_at IS NULL
+    "#,
+    )
+    .fetch_one(&app.pool)
+    .await
+    .unwrap();
+    assert_eq!(live_sessions, 0);
+}
@@ -0,0 +1,82 @@
+use super::TestApp;
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+use std::collections::HashSet;
+
+async fn add_expense(app: &TestApp, token: Option<&str>, desc: &str) {
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/home/expense/add",
+            token,
+            Some(json!({"expense_desc": desc, "amount": "3.20", "category": "Food"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn relevance_pages_neither_skip_nor_repeat_while_scores_shift() {
+    let app = TestApp::spawn().await;
+    let alice = app.login_new_user("alice").await;
+    let alice = alice["token"].as_str();
+    let bob = app.login_new_user("bob").await;
+    let bob = bob["token"].as_str();
+
+    // Longer descriptions score lower, so every match has its own score.
+    let mut filler = String::new();
+    for _ in 0..9 {
+        add_expense(&app, alice, &format!("coffee{filler}")).await;
+        filler.push_str(" beans");
+    }
+    for _ in 0..5 {
+        add_expense(&app, bob, "rent").await;
+    }
+
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let path = match &cursor {
+            Some(cursor) => format!("/home/expense/list?search=coffee&limit=2&cursor={cursor}"),
+            None => "/home/expense/list?search=coffee&limit=2".to_string(),
+        };
+        let (status, page) = app.request(Method::GET, &path, alice, None).await;
+        assert_eq!(status, StatusCode::OK);
+        for expense in page["Expenses"].as_array().unwrap() {
+            seen.push(expense["id"].as_i64().unwrap());
+        }
+
+        // Another user's rows change the shared index statistics, and with
+        // them every score, between pages.
+        for _ in 0..5 {
+            add_expense(&app, bob, "rent").await;
+        }
+
+        match page["next_cursor"].as_str() {
+            Some(next) => cursor = Some(next.to_string()),
+            None => break,
+        }
+    }
+
+    assert_eq!(seen.len(), 9);
+    assert_eq!(seen.iter().collect::<HashSet<_>>().len(), 9);
+}
+
+#[tokio::test]
+async fn search_does_not_match_amounts_in_minor_units() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("carol").await;
+    let token = login["token"].as_str();
+    add_expense(&app, token, "coffee").await;
+
+    let (status, page) = app
+        .request(Method::GET, "/home/expense/list?search=320", token, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["Expenses"], json!([]));
+
+    let (_, page) = app
+        .request(Method::GET, "/home/expense/list?search=coffee", token, None)
+        .await;
+    assert_eq!(page["Expenses"].as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,78 @@
+use super::{PASSWORD, TestApp};
+use crate::api::totp::{TOTP_STEP_SECS, hotp};
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+#[tokio::test]
+async fn a_code_is_accepted_only_once() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("twofactor").await;
+    let token = login["token"].as_str();
+
+    let (status, enrolled) = app
+        .request(Method::POST, "/me/2fa/enroll", token, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let secret = BASE32_NOPAD
+        .decode(enrolled["secret"].as_str().unwrap().as_bytes())
+        .unwrap();
+
+    let step = Utc::now().timestamp() / TOTP_STEP_SECS;
+    let code = format!("{:06}", hotp(&secret, step));
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/me/2fa/confirm",
+            token,
+            Some(json!({"code": code})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let challenge = || async {
+        let (status, body) = app
+            .request(
+                Method::POST,
+                "/login",
+                None,
+                Some(json!({"username": "twofactor", "password": PASSWORD})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        body["challenge_token"].clone()
+    };
+
+    // The confirmation used this step up, so the same code cannot log in.
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/login/2fa",
+            None,
+            Some(json!({"challenge_token": challenge().await, "code": code})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let next_code = format!("{:06}", hotp(&secret, step + 1));
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/login/2fa",
+            None,
+            Some(json!({"challenge_token": challenge().await, "code": next_code})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let last_step: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT totp_last_step FROM users WHERE username = 'twofactor'
+    "#,
+    )
+    .fetch_one(&app.pool)
+    .await
+    .unwrap();
+    assert_eq!(last_step, Some(step + 1));
+}
@@ -0,0 +1,123 @@
+use super::TestApp;
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+#[tokio::test]
+async fn a_rejected_base_currency_change_leaves_the_profile_untouched() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("frank").await;
+    let token = login["token"].as_str();
+
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/home/expense/add",
+            token,
+            Some(json!({"expense_desc": "lunch", "amount": "12.50", "category": "Food"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // No USD to EUR rate exists, so the expense cannot be converted.
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            "/me",
+            token,
+            Some(json!({
+                "username": "franklin",
+                "timezone": "Europe/Berlin",
+                "max_expense_amount": 50,
+                "base_currency": "EUR",
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, me) = app.request(Method::GET, "/me", token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(me["user"]["username"], "frank");
+    assert_eq!(me["user"]["timezone"], "UTC");
+    assert_eq!(me["user"]["base_currency"], "USD");
+    assert_eq!(me["user"]["max_expense_amount"], json!(null));
+}
+
+#[tokio::test]
+async fn a_base_currency_change_converts_the_amount_limits() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("grace").await;
+    let token = login["token"].as_str();
+    sqlx::query(
+        "INSERT INTO exchange_rates (base, quote, rate_on, rate) VALUES ('USD', 'JPY', '2000-01-01', 150.0)",
+    )
+    .execute(&app.pool)
+    .await
+    .unwrap();
+
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            "/me",
+            token,
+            Some(json!({"max_expense_amount": 1000, "confirm_above": 500})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app
+        .request(
+            Method::PATCH,
+            "/me",
+            token,
+            Some(json!({"base_currency": "JPY"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["user"]["base_currency"], "JPY");
+    assert_eq!(body["user"]["max_expense_amount"], 150_000);
+    assert_eq!(body["user"]["confirm_above"], 75_000);
+
+    // Limits sent with the change are already in the new currency.
+    let (status, body) = app
+        .request(
+            Method::PATCH,
+            "/me",
+            token,
+            Some(json!({"base_currency": "USD", "max_expense_amount": 2000})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["user"]["max_expense_amount"], 2000);
+    assert_eq!(body["user"]["confirm_above"], 500);
+}
+
+#[tokio::test]
+async fn limits_without_a_rate_block_the_base_currency_change() {
+    let app = TestApp::spawn().await;
+    let login = app.login_new_user("heidi").await;
+    let token = login["token"].as_str();
+
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            "/me",
+            token,
+            Some(json!({"max_expense_amount": 1000})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            "/me",
+            token,
+            Some(json!({"base_currency": "JPY"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, me) = app.request(Method::GET, "/me", token, None).await;
+    assert_eq!(me["user"]["base_currency"], "USD");
+    assert_eq!(me["user"]["max_expense_amount"], 1000);
+}